@@ -0,0 +1,50 @@
+//! # Send
+//! Criterion benchmark for the throughput of local message sends.
+//! Run with `cargo bench --features test-util`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use fluxion::{message, test_util::Benchmark, Actor, ActorContext, Delegate, Fluxion, Handler};
+
+
+/// # [`TestActor`]
+/// Xors the data in each message with a stored value.
+struct TestActor(pub u64);
+
+impl Actor for TestActor {
+    type Error = ();
+}
+
+/// # [`TestMessage`]
+/// The 64-bit integer sent to the actor.
+#[message(u64)]
+struct TestMessage(pub u64);
+
+impl Handler<TestMessage> for TestActor {
+    async fn handle_message<D: Delegate>(&self, message: TestMessage, _context: &ActorContext<D>) -> u64 {
+        message.0 ^ self.0
+    }
+}
+
+
+fn send(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let system = Fluxion::new("benchmark", ());
+
+    let mut group = c.benchmark_group("send");
+    for messages in [1_000, 10_000] {
+        group.throughput(Throughput::Elements(messages as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(messages), &messages, |b, &messages| {
+            b.to_async(&runtime).iter(|| async {
+                Benchmark::new(1, messages)
+                    .warmup(0)
+                    .run(&system, |i| TestActor(i as u64), |i| TestMessage(i as u64))
+                    .await
+                    .unwrap()
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, send);
+criterion_main!(benches);
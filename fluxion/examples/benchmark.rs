@@ -1,6 +1,6 @@
-/// # Benchmark
-/// Extremely simple benchmark for Fluxion's message passing speed.
-/// May not be entirely representitive of real usecases.
+//! # Benchmark
+//! Extremely simple benchmark for Fluxion's message passing speed.
+//! May not be entirely representitive of real usecases.
 
 
 // Imports from Fluxion that are needed for this example
@@ -40,11 +40,8 @@ async fn main() {
     // Create the system
     let system = Fluxion::new("system", ());
     
-    // Add the actor, returning the ID
-    let id = system.add(TestActor(rand::random())).await.unwrap();
-
-    // Get a local reference to the actor
-    let actor = system.get_local::<TestActor>(id).await.unwrap();
+    // Add the actor, returning a local reference to it
+    let actor = system.add(TestActor(rand::random())).await.unwrap();
 
     // Test with 1 billion messages.
     // If this takes too long, lower values also
@@ -1,16 +1,19 @@
 //! # Foreign messages
 //! This is a rather convoluted example of using foreign messages. It really only exists to show that it can be done.
-//! To run this example, make sure to enable the serde and foreign features.
+//! To run this example, make sure to enable the serde, foreign, and bincode features.
 
 use std::{collections::HashMap, marker::PhantomData, sync::Arc};
 
 
-use fluxion::{actor, message, Delegate, Handler, Identifier, LocalRef, Message, MessageID, MessageSendError, MessageSender};
+use fluxion::{actor, message, BincodeCodec, Codec, Delegate, Handler, Identifier, LocalRef, Message, MessageID, MessageSendError, MessageSender};
 use maitake_sync::RwLock;
 use serde::{Deserialize, Serialize};
 use slacktor::{ActorHandle, Slacktor};
 use tokio::sync::{mpsc, oneshot};
 
+/// The codec used to encode messages sent between the two delegates.
+/// Any other [`Codec`], such as `fluxion::PostcardCodec`, can be swapped in here.
+const CODEC: BincodeCodec = BincodeCodec;
 
 #[actor]
 struct ActorA;
@@ -25,6 +28,11 @@ impl Handler<MessageB> for ActorA {
         println!("Actor {}:{} received {}", context.system().get_id(), context.get_id(), MessageB::ID);
     }
 }
+impl Handler<MessageC> for ActorA {
+    async fn handle_message<D: fluxion::Delegate>(&self, message: MessageC, context: &fluxion::ActorContext<D>) -> <MessageC as fluxion::Message>::Result {
+        println!("Actor {}:{} received {} with {} bytes", context.system().get_id(), context.get_id(), MessageC::ID, message.0.len());
+    }
+}
 
 #[actor]
 struct ActorB;
@@ -50,10 +58,15 @@ struct MessageA;
 #[derive(Serialize, Deserialize)]
 struct MessageB;
 
+#[message]
+#[derive(Serialize, Deserialize)]
+struct MessageC(Vec<u8>);
+
 
 struct DelegateSender<M: Message + MessageID> {
     actor_id: u64,
     other_delegate: ActorHandle<DelegateActor>,
+    max_payload_size: Option<usize>,
     _phantom: PhantomData<M>,
 }
 
@@ -62,11 +75,26 @@ impl<M: Message + MessageID + Serialize> fluxion::MessageSender<M> for DelegateS
 where M::Result: for<'de> Deserialize<'de> {
    
     async fn send(&self,message:M) -> Result<M::Result, MessageSendError> {
+        // Serialize the message
+        let payload = CODEC.encode(&message).unwrap();
+
+        // Reject the message if it is too large for the transport
+        if let Some(limit) = self.max_payload_size {
+            if payload.len() > limit {
+                return Err(MessageSendError::PayloadTooLarge { size: payload.len(), limit });
+            }
+        }
+
         // Send the message
-        let res = self.other_delegate.send(DelegateMessage(self.actor_id, M::ID.to_string(), bincode::serialize(&message).unwrap())).await;
+        let res = self.other_delegate.send(DelegateMessage(self.actor_id, M::ID.to_string(), payload)).await;
+
+        // If the handler's channel has closed, then the actor no longer exists.
+        let Some(res) = res else {
+            return Err(MessageSendError::ActorNotFound);
+        };
 
         // Deserialize the response
-        Ok(bincode::deserialize(&res).unwrap())
+        Ok(CODEC.decode(&res).unwrap())
     }
 }
 
@@ -79,25 +107,30 @@ impl slacktor::actor::Handler<DelegateMessage> for DelegateActor {
         
         // Get the channel
         let handlers = self.0.actor_handlers.read().await;
-        let channels = handlers.get(&(message.0, message.1)).unwrap();
+        let channels = handlers.get(&(message.0, message.1))?;
 
         // Construct the response oneshot
         let (response_sender, response) = oneshot::channel();
 
-        // Send the message
-        channels.send((message.2, response_sender)).await.unwrap();
+        // Send the message. If either half of the exchange is closed,
+        // then the handler task has stopped, and the actor is gone.
+        channels.send((message.2, response_sender)).await.ok()?;
 
         // Wait for the response and return
-        response.await.unwrap()
+        response.await.ok()
     }
 }
 
 struct DelegateMessage(u64, String, Vec<u8>);
 
 impl slacktor::Message for DelegateMessage {
-    type Result = Vec<u8>;
+    type Result = Option<Vec<u8>>;
 }
 
+/// A channel over which serialized messages are sent to an actor's handler task,
+/// along with a oneshot used to return the serialized response.
+type HandlerChannel = mpsc::Sender<(Vec<u8>, oneshot::Sender<Vec<u8>>)>;
+
 struct SerdeDelegate {
     // The system's id
     system_id: &'static str,
@@ -106,7 +139,9 @@ struct SerdeDelegate {
     // The other delegate's id,
     other_id: usize,
     // Hashmap of message handling channels for actors
-    actor_handlers: RwLock<HashMap<(u64, String), mpsc::Sender<(Vec<u8>, oneshot::Sender<Vec<u8>>)>>>
+    actor_handlers: RwLock<HashMap<(u64, String), HandlerChannel>>,
+    // The largest serialized message that will be sent to the other delegate, if any
+    max_payload_size: Option<usize>,
 }
 
 
@@ -116,17 +151,32 @@ impl SerdeDelegate {
             system_id,
             slacktor,
             other_id,
-            actor_handlers: Default::default()
+            actor_handlers: Default::default(),
+            max_payload_size: None,
         }
     }
 
+    /// Rejects outgoing messages that serialize to more than `limit` bytes.
+    pub fn with_max_payload_size(mut self, limit: usize) -> Self {
+        self.max_payload_size = Some(limit);
+        self
+    }
+
 
     /// Registers an actor as being able to receive a specific message type.
-    pub async fn register_actor_message<A: Handler<M>, M: fluxion::IndeterminateMessage, S: Delegate + AsRef<Self>>(&self, actor: LocalRef<A, S>)
+    /// Returns an error if the actor already has a handler registered for a message with the same id,
+    /// as messages are routed by id alone, and the two would be indistinguishable.
+    pub async fn register_actor_message<A: Handler<M>, M: fluxion::IndeterminateMessage, S: Delegate + AsRef<Self>>(&self, actor: LocalRef<A, S>) -> Result<(), DuplicateMessageId>
         where M::Result: serde::Serialize + for<'de> serde::Deserialize<'de>{
         
         let id = actor.get_id();
 
+        // Lock the handlers for the whole registration, so the check can't race with another registration
+        let mut handlers = self.actor_handlers.write().await;
+        if handlers.contains_key(&(id, M::ID.to_string())) {
+            return Err(DuplicateMessageId { actor: id, message: M::ID });
+        }
+
         println!("{} is registering actor with id {} to handle message {}", self.system_id, id, M::ID);
 
         // Create channels
@@ -141,19 +191,40 @@ impl SerdeDelegate {
                 };
 
                 // Decode the message
-                let decoded: M = bincode::deserialize(&next_message.0).unwrap();
+                let decoded: M = CODEC.decode(&next_message.0).unwrap();
 
                 // Handle the message
                 let res = actor.send(decoded).await.expect("this delegate doesn't error");
 
                 // Send the response
-                next_message.1.send(bincode::serialize(&res).unwrap()).unwrap();
+                next_message.1.send(CODEC.encode(&res).unwrap()).unwrap();
             }
         });
 
         // Add the handler
-        self.actor_handlers.write().await.insert((id, M::ID.to_string()), send_message);
+        handlers.insert((id, M::ID.to_string()), send_message);
+
+        Ok(())
+    }
 
+    /// Returns every registered pair of actor id and message id, for debugging routing problems.
+    pub async fn registered_message_ids(&self) -> Vec<(u64, String)> {
+        let mut ids: Vec<_> = self.actor_handlers.read().await.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+}
+
+/// Returned when registering a handler for a message id that is already registered on the same actor
+#[derive(Debug)]
+struct DuplicateMessageId {
+    actor: u64,
+    message: &'static str,
+}
+
+impl std::fmt::Display for DuplicateMessageId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "actor {} already has a handler for message id {}", self.actor, self.message)
     }
 }
 
@@ -177,9 +248,39 @@ impl Delegate for SerdeDelegate {
         Some(Arc::new(DelegateSender {
             actor_id: id,
             other_delegate: other.clone(),
+            max_payload_size: self.max_payload_size,
             _phantom: PhantomData,
         }))
     }
+
+    async fn is_connected(&self) -> bool {
+        // The transport is usable as long as the other delegate's actor still exists on the backplane
+        self.slacktor.read().await.get::<DelegateActor>(self.other_id).is_some()
+    }
+
+    async fn get_actors<'a, A: Handler<M>, M: fluxion::IndeterminateMessage>(&self, ids: &[Identifier<'a>]) -> Vec<Option<Arc<dyn MessageSender<M>>>>
+        where M::Result: serde::Serialize + for<'de> serde::Deserialize<'de> {
+
+        println!("{} is requesting {} foreign actors that can handle message {} in one batch", self.system_id, ids.len(), M::ID);
+
+        // Look up the other delegate once for the whole batch, instead of once per actor
+        let slacktor = self.slacktor.read().await;
+        let other = slacktor.get::<DelegateActor>(self.other_id);
+
+        ids.iter().map(|id| {
+            let Identifier::Foreign(id, _) = id else {
+                return None;
+            };
+
+            let sender: Arc<dyn MessageSender<M>> = Arc::new(DelegateSender {
+                actor_id: *id,
+                other_delegate: other?.clone(),
+                max_payload_size: self.max_payload_size,
+                _phantom: PhantomData,
+            });
+            Some(sender)
+        }).collect()
+    }
 }
 
 #[tokio::main]
@@ -188,11 +289,12 @@ async fn main() {
     // This basic example just uses slacktor as the communication medium between delegates.
     // In practice, any system that can provide request/response semantics can be used to create a delegate.
     let delegate_backplane = Arc::new(RwLock::new(Slacktor::new()));
+    let backplane_handle = delegate_backplane.clone();
     let backplane = delegate_backplane.clone();
     let mut backplane = backplane.write().await;
 
     let delegate_a = Arc::new(SerdeDelegate::new("system_a", delegate_backplane.clone(), 1));
-    let delegate_b = Arc::new(SerdeDelegate::new("system_a", delegate_backplane, 0));
+    let delegate_b = Arc::new(SerdeDelegate::new("system_a", delegate_backplane, 0).with_max_payload_size(1024));
 
     backplane.spawn(DelegateActor(delegate_a.clone()));
     backplane.spawn(DelegateActor(delegate_b.clone()));
@@ -207,18 +309,73 @@ async fn main() {
 
     // Create both actors on system a
     let actor_a = system_a.add(ActorA).await.unwrap();
-    system_a.get_delegate().register_actor_message::<ActorA, MessageA, _>(system_a.get_local(actor_a).await.unwrap()).await;
-    system_a.get_delegate().register_actor_message::<ActorA, MessageB, _>(system_a.get_local(actor_a).await.unwrap()).await;
+    system_a.get_delegate().register_actor_message::<ActorA, MessageA, _>(actor_a.clone()).await.unwrap();
+    system_a.get_delegate().register_actor_message::<ActorA, MessageB, _>(actor_a.clone()).await.unwrap();
+    system_a.get_delegate().register_actor_message::<ActorA, MessageC, _>(actor_a.clone()).await.unwrap();
     let actor_b = system_a.add(ActorB).await.unwrap();
-    system_a.get_delegate().register_actor_message::<ActorB, MessageA, _>(system_a.get_local(actor_b).await.unwrap()).await;
-    system_a.get_delegate().register_actor_message::<ActorB, MessageB, _>(system_a.get_local(actor_b).await.unwrap()).await;
+    system_a.get_delegate().register_actor_message::<ActorB, MessageA, _>(actor_b.clone()).await.unwrap();
+    system_a.get_delegate().register_actor_message::<ActorB, MessageB, _>(actor_b.clone()).await.unwrap();
+
+    // Registering the same message id on an actor twice is rejected, instead of silently replacing the first handler.
+    let err = system_a.get_delegate().register_actor_message::<ActorB, MessageB, _>(actor_b.clone()).await.unwrap_err();
+    println!("{err}");
+    println!("system_a has registered {:?}", system_a.get_delegate().registered_message_ids().await);
 
     
    
     // Get both actors on system b
-    let foreign_a = system_b.get::<ActorA, MessageB>(Identifier::Foreign(actor_a, "system_a")).await.unwrap();
-    let foreign_b = system_b.get::<ActorB, MessageA>(Identifier::Foreign(actor_b, "system_a")).await.unwrap();
+    let foreign_a = system_b.get::<ActorA, MessageB>(Identifier::Foreign(actor_a.get_id(), "system_a")).await.unwrap();
+    let foreign_b = system_b.get::<ActorB, MessageA>(Identifier::Foreign(actor_b.get_id(), "system_a")).await.unwrap();
 
     foreign_a.send(MessageB).await.expect("this delegate doesn't error");
     foreign_b.send(MessageA).await.expect("this delegate doesn't error");
-}
\ No newline at end of file
+
+    // System b's delegate rejects payloads larger than 1024 bytes before they reach the transport.
+    let foreign_c = system_b.get::<ActorA, MessageC>(Identifier::Foreign(actor_a.get_id(), "system_a")).await.unwrap();
+
+    foreign_c.send(MessageC(vec![0; 512])).await.expect("this message is within the limit");
+    let err = foreign_c.send(MessageC(vec![0; 4096])).await.expect_err("this message exceeds the limit");
+    assert!(matches!(err, MessageSendError::PayloadTooLarge { limit: 1024, .. }));
+    println!("{err}");
+
+    assert!(system_b.get_delegate().is_connected().await);
+
+    // Both actors can also be resolved in a single batch.
+    let ids = [Identifier::Foreign(actor_a.get_id(), "system_a"), Identifier::Foreign(actor_b.get_id(), "system_a")];
+    let senders = system_b.get_delegate().get_actors::<ActorA, MessageA>(&ids).await;
+
+    for sender in senders {
+        sender.expect("both actors exist").send(MessageA).await.expect("this delegate doesn't error");
+    }
+
+    // Once system a's delegate is gone from the backplane, system b fails fast instead of handing out dead senders.
+    backplane_handle.write().await.kill::<DelegateActor>(0).await;
+    let err = system_b.get::<ActorA, MessageA>(Identifier::Foreign(actor_a.get_id(), "system_a")).await.err();
+    assert_eq!(err, Some(fluxion::SystemError::Disconnected));
+    println!("{}", err.unwrap());
+}
+
+
+#[tokio::test]
+async fn duplicate_message_ids_are_rejected() {
+    let backplane = Arc::new(RwLock::new(Slacktor::new()));
+    let delegate = Arc::new(SerdeDelegate::new("system", backplane, 0));
+    let system = fluxion::Fluxion::new("system", delegate);
+
+    let actor = system.add(ActorB).await.unwrap();
+    system.get_delegate().register_actor_message::<ActorB, MessageA, _>(actor.clone()).await.unwrap();
+    system.get_delegate().register_actor_message::<ActorB, MessageB, _>(actor.clone()).await.unwrap();
+
+    let err = system.get_delegate().register_actor_message::<ActorB, MessageB, _>(actor.clone()).await.unwrap_err();
+    assert_eq!(err.actor, actor.get_id());
+    assert_eq!(err.message, MessageB::ID);
+
+    // The first handler is kept, and no handler is registered twice
+    let registered = system.get_delegate().registered_message_ids().await;
+    assert_eq!(registered, [(actor.get_id(), MessageA::ID.to_string()), (actor.get_id(), MessageB::ID.to_string())]);
+}
+
+#[test]
+fn main_runs() {
+    main();
+}
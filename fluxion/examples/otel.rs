@@ -0,0 +1,89 @@
+//! # OpenTelemetry
+//! This example exports the metrics of a Fluxion system through an OpenTelemetry meter provider.
+//! To run this example, make sure to enable the otel feature.
+//! A real application would install an exporter for its observability stack, such as OTLP, in place of the in-memory exporter used here.
+
+use std::{future::Future, pin::Pin, time::{Duration, Instant}};
+
+use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSender, OtelMetrics, Timer};
+use opentelemetry::metrics::MeterProvider;
+use opentelemetry_sdk::metrics::{data::{AggregatedMetrics, MetricData}, InMemoryMetricExporter, PeriodicReader, SdkMeterProvider};
+
+/// A timer that can measure handling times, so that they are exported too
+struct TokioTimer(Instant);
+
+impl Timer for TokioTimer {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    fn now(&self) -> Option<Duration> {
+        Some(self.0.elapsed())
+    }
+}
+
+#[actor]
+struct Resizer;
+
+#[message(usize)]
+struct Resize(usize);
+
+impl Handler<Resize> for Resizer {
+    async fn handle_message<D: Delegate>(&self, message: Resize, _context: &ActorContext<D>) -> usize {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        message.0 / 2
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    // Set up the meter provider, which periodically collects every instrument and hands them to the exporter
+    let exporter = InMemoryMetricExporter::default();
+    let provider = SdkMeterProvider::builder()
+        .with_reader(PeriodicReader::builder(exporter.clone()).build())
+        .build();
+
+    // Install the instruments on a system
+    let system = Fluxion::new("system", ())
+        .with_timer(TokioTimer(Instant::now()))
+        .with_metrics(OtelMetrics::new(&provider.meter("fluxion")));
+
+    let resizer = system.add(Resizer).await.unwrap();
+    for size in [256, 512, 1024] {
+        println!("Resized {size} to {}", resizer.send(Resize(size)).await.unwrap());
+    }
+
+    // Collect the instruments now instead of waiting for the next period, and print what was exported
+    provider.force_flush().unwrap();
+    let mut handled = 0;
+    for resource in exporter.get_finished_metrics().unwrap() {
+        for metric in resource.scope_metrics().flat_map(|scope| scope.metrics()) {
+            match metric.data() {
+                AggregatedMetrics::U64(MetricData::Sum(sum)) => {
+                    let total = sum.data_points().map(|point| point.value()).sum::<u64>();
+                    if metric.name() == "fluxion.messages.handled" {
+                        handled = total;
+                    }
+                    println!("{}: {total}", metric.name());
+                },
+                AggregatedMetrics::I64(MetricData::Sum(sum)) => {
+                    println!("{}: {}", metric.name(), sum.data_points().map(|point| point.value()).sum::<i64>());
+                },
+                AggregatedMetrics::F64(MetricData::Histogram(histogram)) => {
+                    for point in histogram.data_points() {
+                        println!("{}: {} messages, {:.3}s in total", metric.name(), point.count(), point.sum());
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+
+    assert_eq!(handled, 3);
+    provider.shutdown().unwrap();
+}
+
+#[test]
+fn main_runs() {
+    main();
+}
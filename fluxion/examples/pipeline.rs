@@ -0,0 +1,84 @@
+//! # Pipeline
+//! Connects a parser actor to a writer actor with [`Fluxion::pipe`], so that every line
+//! sent through the pipe is parsed, and the parsed record is written.
+
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler};
+
+
+/// # [`Record`]
+/// A key/value pair parsed from a line of text.
+#[derive(Debug)]
+struct Record {
+    key: String,
+    value: u64,
+}
+
+/// # [`Parser`]
+/// Parses lines of the form `key=value` into [`Record`]s.
+#[actor]
+struct Parser;
+
+/// # [`Parse`]
+/// A line of text to parse.
+#[message(Option<Record>)]
+struct Parse(String);
+
+impl Handler<Parse> for Parser {
+    async fn handle_message<D: Delegate>(&self, message: Parse, _context: &ActorContext<D>) -> Option<Record> {
+        let (key, value) = message.0.split_once('=')?;
+
+        Some(Record {
+            key: key.trim().to_string(),
+            value: value.trim().parse().ok()?,
+        })
+    }
+}
+
+/// # [`Writer`]
+/// Writes records to stdout, keeping a running total of their values.
+#[actor]
+struct Writer {
+    total: AtomicU64,
+}
+
+/// # [`Write`]
+/// A record to write, or [`None`] if the line could not be parsed.
+/// Responds with the running total.
+#[message(u64)]
+struct Write(Option<Record>);
+
+impl Handler<Write> for Writer {
+    async fn handle_message<D: Delegate>(&self, message: Write, _context: &ActorContext<D>) -> u64 {
+        match message.0 {
+            Some(record) => {
+                println!("{} = {}", record.key, record.value);
+                self.total.fetch_add(record.value, Ordering::Relaxed) + record.value
+            },
+            None => {
+                println!("skipping invalid line");
+                self.total.load(Ordering::Relaxed)
+            },
+        }
+    }
+}
+
+
+#[tokio::main]
+async fn main() {
+    let system = Fluxion::new("system", ());
+
+    let parser = system.add_id(Parser).await.unwrap();
+    let writer = system.add_id(Writer { total: AtomicU64::new(0) }).await.unwrap();
+
+    // Feed the parser's output into the writer.
+    // The message types are inferred from the transform.
+    let pipe = system.pipe::<Parser, Writer, _, _>(parser, writer, Write).await.unwrap();
+
+    for line in ["apples = 3", "pears=5", "not a record", "plums = 2"] {
+        let total = pipe.send(Parse(line.to_string())).await.unwrap();
+        println!("running total: {total}");
+    }
+}
@@ -29,6 +29,7 @@ struct TestActor;
 /// Here we use the full syntax, but it can be reduced to simply `#[message]`, and the effect will be the same. 
 /// The default response type is `()` and the default ID for a message is it's full module path.
 #[message((), "simple::TestMessage")]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct TestMessage;
 
 
@@ -63,10 +64,13 @@ async fn main() {
     // Here we create it with an empty delegate (the unit type)
     // which just means that foreign messages are disabled for this system.
     // Any requests for foreign messages will just return [`None`].
-    let system = Fluxion::new("system", ());
+    // Because the empty delegate is the default, the system's type can be written as just `Fluxion`.
+    let system: Fluxion = Fluxion::new("system", ());
     
     // Adding an actor to the system assigns it with an ID.
-    let id = system.add(TestActor).await.unwrap();
+    // If you only need the ID, use [`Fluxion::add_id`]. [`Fluxion::add`] also returns a local reference to the actor,
+    // which is the same as the one retrieved by [`Fluxion::get_local`] below.
+    let id = system.add_id(TestActor).await.unwrap();
 
     // You can use this ID to retrieve a reference to the actor.
     // There are two ways to do this.
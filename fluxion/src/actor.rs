@@ -1,9 +1,10 @@
 //! # Actors
 //! This module contains traits and other types and implementations surrounding actors and how they interface with the system. 
 
-use alloc::sync::Arc;
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::{future::Future, pin::Pin, time::Duration};
 
-use crate::{Delegate, Fluxion, Message};
+use crate::{AddError, Delegate, Fluxion, LocalRef, Message, MessageSender, ScheduleHandle, SystemError, Timer, ValidationError};
 
 
 
@@ -19,6 +20,31 @@ pub trait Actor: Send + Sync + 'static {
     /// can be returned by methods defined by this trait.
     type Error;
 
+    /// # [`MANUAL_READINESS`]
+    /// If `false` (the default), the actor is considered ready as soon as [`Actor::initialize`] succeeds.
+    /// If `true`, the actor must instead mark itself as ready by calling [`ActorContext::mark_ready`],
+    /// which is useful for actors that finish initializing after they have been added.
+    /// Readiness can be awaited with [`Fluxion::await_ready`].
+    const MANUAL_READINESS: bool = false;
+
+    /// # [`MAILBOX_CAPACITY`]
+    /// The maximum number of messages that may be in flight to this actor at once, or [`None`] (the default) for no limit.
+    /// Once the limit is reached, [`LocalRef`] sends wait until an earlier message has been handled,
+    /// and [`LocalRef::try_send`] fails with [`MessageSendError::MailboxFull`](crate::MessageSendError::MailboxFull).
+    /// This applies backpressure to producers that would otherwise outpace a slow handler.
+    ///
+    /// <div class = "info">
+    /// Actors do not have a queue, so this limits how many sends may run the actor's handlers concurrently.
+    /// Setting this to `Some(0)` prevents any message from ever being handled.
+    /// </div>
+    const MAILBOX_CAPACITY: Option<usize> = None;
+
+    /// # [`SHUTDOWN_PRIORITY`]
+    /// Determines the order in which actors are deinitialized by [`Fluxion::shutdown`].
+    /// Actors with a lower priority are deinitialized first, and actors with the same priority are deinitialized concurrently.
+    /// The default priority is `0`. This has no effect on actors killed individually.
+    const SHUTDOWN_PRIORITY: i32 = 0;
+
     /// # [`initialize`]
     /// Called immediately before the actor is added to the system.
     fn initialize(&mut self) -> impl core::future::Future<Output = Result<(), Self::Error>> + Send {async {
@@ -35,11 +61,16 @@ pub trait Actor: Send + Sync + 'static {
 
 /// # [`ActorContext`]
 /// Provides an actor with access to the system and to metadata about itself
-pub struct ActorContext<D> {
+pub struct ActorContext<D = ()> {
     /// The underlying system
     pub(crate) system: Fluxion<D>,
     /// The actor's id
     pub(crate) id: u64,
+    /// The id of the actor that spawned this actor via [`ActorContext::add_child`], if any
+    pub(crate) parent: Option<u64>,
+    /// The actor's counters, which are reported by [`Fluxion::metrics_snapshot`]
+    #[cfg(feature = "metrics")]
+    pub(crate) counters: Arc<crate::metrics::ActorCounters>,
 }
 
 impl<D: Delegate> ActorContext<D> {
@@ -56,11 +87,823 @@ impl<D: Delegate> ActorContext<D> {
     pub fn system(&self) -> &Fluxion<D> {
         &self.system
     }
+
+    /// # [`ActorContext::state`]
+    /// Retrieves the value of type `T` from the system's shared state,
+    /// or [`None`] if no such value was added with [`Fluxion::with_state`].
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSender};
+    /// struct Config {
+    ///     greeting: &'static str,
+    /// }
+    ///
+    /// #[actor] struct English;
+    /// #[actor] struct Shouter;
+    /// #[message(String)] struct Greet;
+    ///
+    /// impl Handler<Greet> for English {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Greet, context: &ActorContext<D>) -> String {
+    ///         context.state::<Config>().unwrap().greeting.to_string()
+    ///     }
+    /// }
+    ///
+    /// impl Handler<Greet> for Shouter {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Greet, context: &ActorContext<D>) -> String {
+    ///         context.state::<Config>().unwrap().greeting.to_uppercase()
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ()).with_state(Config { greeting: "hello" });
+    ///
+    /// let english = system.add(English).await.unwrap();
+    /// let shouter = system.add(Shouter).await.unwrap();
+    ///
+    /// assert_eq!(english.send(Greet).await.unwrap(), "hello");
+    /// assert_eq!(shouter.send(Greet).await.unwrap(), "HELLO");
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn state<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.system.state()
+    }
+
+    /// # [`ActorContext::parent`]
+    /// Returns the id of the actor that spawned this actor,
+    /// or [`None`] if this actor was added directly to the system.
+    #[must_use]
+    pub fn parent(&self) -> Option<u64> {
+        self.parent
+    }
+
+    /// # [`ActorContext::children`]
+    /// Returns the ids of the actors this actor has added with [`ActorContext::add_child`] that are still alive.
+    pub async fn children(&self) -> Vec<u64> {
+        self.system.children(self.id).await
+    }
+
+    /// # [`ActorContext::get_parent`]
+    /// Retrieves a reference to this actor's parent, given the parent's type.
+    /// Returns [`None`] if this actor has no parent, if the parent no longer exists,
+    /// or if the parent is not of type `PA`.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSender};
+    /// # use std::sync::atomic::{AtomicU64, Ordering};
+    /// static FINISHED: AtomicU64 = AtomicU64::new(0);
+    ///
+    /// #[actor]
+    /// struct Supervisor;
+    ///
+    /// #[actor]
+    /// struct Job;
+    ///
+    /// #[message(Option<u64>)] struct Parent;
+    /// #[message(u64)] struct Spawn;
+    /// #[message] struct Run;
+    /// #[message] struct Finished;
+    ///
+    /// impl Handler<Parent> for Supervisor {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Parent, context: &ActorContext<D>) -> Option<u64> {
+    ///         context.parent()
+    ///     }
+    /// }
+    ///
+    /// impl Handler<Spawn> for Supervisor {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Spawn, context: &ActorContext<D>) -> u64 {
+    ///         context.add_child(Job).await.unwrap()
+    ///     }
+    /// }
+    ///
+    /// impl Handler<Finished> for Supervisor {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Finished, _context: &ActorContext<D>) {
+    ///         FINISHED.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// impl Handler<Run> for Job {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Run, context: &ActorContext<D>) {
+    ///         // Report back to the supervisor
+    ///         let supervisor = context.get_parent::<Supervisor>().await.unwrap();
+    ///         supervisor.send(Finished).await.unwrap();
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let supervisor = system.add(Supervisor).await.unwrap();
+    ///
+    /// // The supervisor was added directly to the system
+    /// assert_eq!(supervisor.send(Parent).await.unwrap(), None);
+    ///
+    /// let job = supervisor.send(Spawn).await.unwrap();
+    /// system.get_local::<Job>(job).await.unwrap().send(Run).await.unwrap();
+    ///
+    /// assert_eq!(FINISHED.load(Ordering::Relaxed), 1);
+    /// # });
+    /// ```
+    pub async fn get_parent<PA: Actor>(&self) -> Option<LocalRef<PA, D>> {
+        self.system.get_local::<PA>(self.parent?).await.ok()
+    }
+
+    /// # [`ActorContext::self_ref`]
+    /// Retrieves a reference to this actor, given its own type. Inside a handler, this is `Self`.
+    /// Returns [`None`] if this actor has been killed, or if it is not of type `A`.
+    ///
+    /// <div class = "warn">
+    /// Sends to this reference are handled immediately, and the send waits for them to finish.
+    /// If this actor sets [`Actor::MAILBOX_CAPACITY`], a handler awaiting a send to itself may wait forever.
+    /// </div>
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSender};
+    /// #[actor] struct Countdown;
+    /// #[message(u64)] struct Count(u64);
+    ///
+    /// impl Handler<Count> for Countdown {
+    ///     async fn handle_message<D: Delegate>(&self, message: Count, context: &ActorContext<D>) -> u64 {
+    ///         if message.0 == 0 {
+    ///             return 0;
+    ///         }
+    ///
+    ///         // Send a follow-up message to ourselves
+    ///         let this = context.self_ref::<Self>().await.unwrap();
+    ///         1 + this.send(Count(message.0 - 1)).await.unwrap()
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let countdown = system.add(Countdown).await.unwrap();
+    ///
+    /// assert_eq!(countdown.send(Count(3)).await.unwrap(), 3);
+    /// # });
+    /// ```
+    pub async fn self_ref<A: Actor>(&self) -> Option<LocalRef<A, D>> {
+        self.system.get_local::<A>(self.id).await.ok()
+    }
+
+    /// # [`ActorContext::schedule`]
+    /// Sends a message to the given actor once `delay` has elapsed, without waiting for it to be sent.
+    /// The delay is measured using the system's [`Timer`](crate::Timer), and the send runs in a task started by its [`Spawner`].
+    /// The target's response, and any error sending to it, are discarded.
+    ///
+    /// The message is not sent if the returned [`ScheduleHandle`] is dropped before the delay elapses.
+    /// The handle is checked again once the delay has elapsed, so dropping it at any point before the send begins prevents it.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSender, Spawner, Timer};
+    /// # use std::{future::Future, pin::Pin, sync::{Arc, atomic::{AtomicU64, Ordering}}, time::Duration};
+    /// # struct TokioTimer;
+    /// # impl Timer for TokioTimer {
+    /// #     fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> { Box::pin(tokio::time::sleep(duration)) }
+    /// # }
+    /// # struct TokioSpawner;
+    /// # impl Spawner for TokioSpawner {
+    /// #     fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>) { tokio::spawn(future); }
+    /// # }
+    /// static RECEIVED: AtomicU64 = AtomicU64::new(0);
+    ///
+    /// #[actor] struct Receiver;
+    /// #[message] struct Ping(u64);
+    ///
+    /// impl Handler<Ping> for Receiver {
+    ///     async fn handle_message<D: Delegate>(&self, message: Ping, _context: &ActorContext<D>) {
+    ///         RECEIVED.store(message.0, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// #[actor] struct Sender;
+    /// #[message] struct Start(u64);
+    ///
+    /// impl Handler<Start> for Sender {
+    ///     async fn handle_message<D: Delegate>(&self, message: Start, context: &ActorContext<D>) {
+    ///         let receiver = context.system().get_local::<Receiver>(message.0).await.unwrap();
+    ///
+    ///         // Cancelled, as the handle is dropped immediately
+    ///         let _ = context.schedule(Arc::new(receiver.clone()), Ping(1), Duration::from_millis(10)).unwrap();
+    ///
+    ///         context.schedule(Arc::new(receiver), Ping(2), Duration::from_millis(10)).unwrap().detach();
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ()).with_timer(TokioTimer).with_spawner(TokioSpawner);
+    /// let receiver = system.add_id(Receiver).await.unwrap();
+    /// let sender = system.add(Sender).await.unwrap();
+    ///
+    /// sender.send(Start(receiver)).await.unwrap();
+    /// assert_eq!(RECEIVED.load(Ordering::Relaxed), 0);
+    ///
+    /// tokio::time::sleep(Duration::from_millis(100)).await;
+    /// assert_eq!(RECEIVED.load(Ordering::Relaxed), 2);
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`SystemError::NoScheduler`] if the system has no timer or no spawner.
+    #[doc(alias = "send_after")]
+    pub fn schedule<M: Message>(&self, target: Arc<dyn MessageSender<M>>, message: M, delay: Duration) -> Result<ScheduleHandle, SystemError> {
+        let (timer, spawner) = self.system.scheduler().ok_or(SystemError::NoScheduler)?;
+
+        let sleep = timer.sleep(delay);
+        Ok(ScheduleHandle::spawn(spawner, |cancellation| async move {
+            sleep.await;
+
+            // The handle may have been dropped just as the delay elapsed
+            if cancellation.is_cancelled() {
+                return;
+            }
+            let _ = target.send(message).await;
+        }))
+    }
+
+    /// # [`ActorContext::schedule_interval`]
+    /// Sends a copy of `message` to this actor every `period`, given its own type. Inside a handler, this is `Self`.
+    /// Ticks stop once the returned [`ScheduleHandle`] is dropped, or once this actor has been killed.
+    /// No tick is sent after the handle is dropped, but a tick that was already being sent at that moment may still be delivered.
+    /// Each tick waits for the previous one to be handled, so a slow handler delays later ticks rather than piling them up.
+    ///
+    /// <div class = "warn">
+    /// Unless the `deterministic-ids` feature is enabled, ids are reused after an actor is killed.
+    /// If this actor is killed and another actor of the same type is assigned its id before the next tick,
+    /// ticks will be sent to the new actor until the handle is dropped.
+    /// </div>
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSender, ScheduleHandle, Spawner, Timer};
+    /// # use std::{future::Future, pin::Pin, sync::{Mutex, atomic::{AtomicU64, Ordering}}, time::Duration};
+    /// # struct TokioTimer;
+    /// # impl Timer for TokioTimer {
+    /// #     fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> { Box::pin(tokio::time::sleep(duration)) }
+    /// # }
+    /// # struct TokioSpawner;
+    /// # impl Spawner for TokioSpawner {
+    /// #     fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>) { tokio::spawn(future); }
+    /// # }
+    /// #[actor]
+    /// struct Heart {
+    ///     beats: AtomicU64,
+    ///     interval: Mutex<Option<ScheduleHandle>>,
+    /// }
+    ///
+    /// #[message] #[derive(Clone)] struct Beat;
+    /// #[message] struct Start;
+    /// #[message(u64)] struct Stop;
+    ///
+    /// impl Handler<Beat> for Heart {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Beat, _context: &ActorContext<D>) {
+    ///         self.beats.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// impl Handler<Start> for Heart {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Start, context: &ActorContext<D>) {
+    ///         let handle = context.schedule_interval::<Self, _>(Beat, Duration::from_millis(10)).unwrap();
+    ///         *self.interval.lock().unwrap() = Some(handle);
+    ///     }
+    /// }
+    ///
+    /// impl Handler<Stop> for Heart {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Stop, _context: &ActorContext<D>) -> u64 {
+    ///         // Dropping the handle stops the ticks
+    ///         self.interval.lock().unwrap().take();
+    ///         self.beats.load(Ordering::Relaxed)
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ()).with_timer(TokioTimer).with_spawner(TokioSpawner);
+    /// let heart = system.add(Heart { beats: AtomicU64::new(0), interval: Mutex::new(None) }).await.unwrap();
+    ///
+    /// heart.send(Start).await.unwrap();
+    /// tokio::time::sleep(Duration::from_millis(100)).await;
+    ///
+    /// assert!(heart.send(Stop).await.unwrap() > 0);
+    ///
+    /// // Give a tick that was already being sent time to finish
+    /// tokio::time::sleep(Duration::from_millis(50)).await;
+    /// let beats = heart.send(Stop).await.unwrap();
+    ///
+    /// tokio::time::sleep(Duration::from_millis(50)).await;
+    /// assert_eq!(heart.send(Stop).await.unwrap(), beats);
+    /// # });
+    /// ```
+    ///
+    /// Killing the actor also stops its ticks, even if the handle was detached:
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSender, Spawner, Timer};
+    /// # use std::{future::Future, pin::Pin, sync::atomic::{AtomicU64, Ordering}, time::Duration};
+    /// # struct TokioTimer;
+    /// # impl Timer for TokioTimer {
+    /// #     fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> { Box::pin(tokio::time::sleep(duration)) }
+    /// # }
+    /// # struct TokioSpawner;
+    /// # impl Spawner for TokioSpawner {
+    /// #     fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>) { tokio::spawn(future); }
+    /// # }
+    /// static BEATS: AtomicU64 = AtomicU64::new(0);
+    ///
+    /// #[actor] struct Heart;
+    /// #[message] #[derive(Clone)] struct Beat;
+    /// #[message] struct Start;
+    ///
+    /// impl Handler<Beat> for Heart {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Beat, _context: &ActorContext<D>) {
+    ///         BEATS.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// impl Handler<Start> for Heart {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Start, context: &ActorContext<D>) {
+    ///         context.schedule_interval::<Self, _>(Beat, Duration::from_millis(10)).unwrap().detach();
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ()).with_timer(TokioTimer).with_spawner(TokioSpawner);
+    /// let heart = system.add(Heart).await.unwrap();
+    ///
+    /// heart.send(Start).await.unwrap();
+    /// tokio::time::sleep(Duration::from_millis(100)).await;
+    /// assert!(BEATS.load(Ordering::Relaxed) > 0);
+    ///
+    /// system.kill::<Heart>(heart.get_id()).await;
+    ///
+    /// // Give a tick that was already being sent time to finish
+    /// tokio::time::sleep(Duration::from_millis(50)).await;
+    /// let beats = BEATS.load(Ordering::Relaxed);
+    ///
+    /// tokio::time::sleep(Duration::from_millis(50)).await;
+    /// assert_eq!(BEATS.load(Ordering::Relaxed), beats);
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`SystemError::NoScheduler`] if the system has no timer or no spawner.
+    pub fn schedule_interval<A: Handler<M>, M: Message + Clone>(&self, message: M, period: Duration) -> Result<ScheduleHandle, SystemError> {
+        let (timer, spawner) = self.system.scheduler().ok_or(SystemError::NoScheduler)?;
+
+        let system = self.system.clone();
+        let id = self.id;
+        Ok(ScheduleHandle::spawn(spawner, |cancellation| async move {
+            loop {
+                timer.sleep(period).await;
+
+                // The handle may have been dropped just as the period elapsed
+                if cancellation.is_cancelled() {
+                    break;
+                }
+
+                // Look the actor up on every tick, so that the task doesn't keep it alive
+                let Ok(actor) = system.get_local::<A>(id).await else {
+                    break;
+                };
+                let _ = actor.send(message.clone()).await;
+            }
+        }))
+    }
+
+    /// # [`ActorContext::handle_emitting`]
+    /// Runs `actor`'s [`EmittingHandler`] for `message` and returns its response, delivering the emitted messages afterwards.
+    /// This is called from the actor's [`Handler`] implementation for the message. See [`EmittingHandler`] for the delivery order.
+    pub async fn handle_emitting<A: EmittingHandler<M>, M: Message>(&self, actor: &A, message: M) -> M::Result {
+        let (response, emitted) = actor.handle_emitting(message, self).await;
+
+        match self.system.spawner() {
+            Some(spawner) => {
+                let system = self.system.clone();
+                spawner.spawn(Box::pin(async move {
+                    for envelope in emitted {
+                        (envelope.deliver)(&system).await;
+                    }
+                }));
+            },
+            // Without a spawner, there is nothing to deliver the messages once the response has been returned
+            None => for envelope in emitted {
+                (envelope.deliver)(&self.system).await;
+            },
+        }
+
+        response
+    }
+
+    /// # [`ActorContext::mark_ready`]
+    /// Marks this actor as ready, waking any tasks waiting for it in [`Fluxion::await_ready`].
+    /// This only needs to be called by actors that set [`Actor::MANUAL_READINESS`].
+    pub async fn mark_ready(&self) {
+        self.system.mark_ready(self.id).await;
+    }
+
+    /// # [`ActorContext::add_child`]
+    /// Adds an actor to the system as a child of this actor, returning the child's id.
+    /// The child can reach this actor via [`ActorContext::parent`], and is listed by [`ActorContext::children`].
+    /// When this actor is killed, its children are killed along with it.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSender};
+    /// #[actor]
+    /// struct Worker;
+    ///
+    /// #[actor]
+    /// struct Pool;
+    ///
+    /// #[message(Vec<u64>)]
+    /// struct Grow;
+    ///
+    /// impl Handler<Grow> for Pool {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Grow, context: &ActorContext<D>) -> Vec<u64> {
+    ///         context.add_child(Worker).await.unwrap();
+    ///         context.children().await
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let pool = system.add(Pool).await.unwrap();
+    ///
+    /// pool.send(Grow).await.unwrap();
+    /// let workers = pool.send(Grow).await.unwrap();
+    /// assert_eq!(workers.len(), 2);
+    ///
+    /// system.kill::<Pool>(pool.get_id()).await;
+    /// assert_eq!(system.actor_count().await, 0);
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the child failed to initialize, or if the system is at its actor limit.
+    /// Returns [`SystemError::NotFound`] if this actor was killed while the child was initializing.
+    /// On an error, the child will not be spawned.
+    pub async fn add_child<A: Actor>(&self, actor: A) -> Result<u64, AddError<A::Error>> {
+        self.system.add_with_parent(actor, Some(self.id), None).await
+            .map(|child| child.get_id())
+    }
 }
 
 /// # [`Handler`]
+/// Implemented by actors for each message type they can handle.
+///
+/// With the `tracing` feature enabled, every message runs in a `handle_message` span at the debug level,
+/// recording the system id, the actor id, and the message type.
+/// Trace events are emitted within it when the message is received and when the handler returns.
+///
+/// ```
+/// # #[cfg(feature = "tracing")] {
+/// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSender};
+/// # use std::{fmt::Debug, sync::{Arc, Mutex}};
+/// # use tracing::{field::{Field, Visit}, span::{Attributes, Id, Record}, Event, Metadata, Subscriber};
+/// # #[actor] struct Greeter;
+/// # #[message] struct Greet;
+/// # impl Handler<Greet> for Greeter {
+/// #     async fn handle_message<D: Delegate>(&self, _message: Greet, _context: &ActorContext<D>) {}
+/// # }
+/// /// Records the name and fields of every span, and the message of every event
+/// #[derive(Clone, Default)]
+/// struct Recorder(Arc<Mutex<Vec<String>>>);
+///
+/// impl Visit for Recorder {
+///     fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+///         self.0.lock().unwrap().push(format!("{}={value:?}", field.name()));
+///     }
+/// }
+///
+/// impl Subscriber for Recorder {
+///     fn enabled(&self, _metadata: &Metadata<'_>) -> bool { true }
+///     fn new_span(&self, span: &Attributes<'_>) -> Id {
+///         self.0.lock().unwrap().push(span.metadata().name().to_string());
+///         span.record(&mut self.clone());
+///         Id::from_u64(1)
+///     }
+///     fn record(&self, _span: &Id, _values: &Record<'_>) {}
+///     fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+///     fn event(&self, event: &Event<'_>) { event.record(&mut self.clone()); }
+///     fn enter(&self, _span: &Id) {}
+///     fn exit(&self, _span: &Id) {}
+/// }
+///
+/// let recorder = Recorder::default();
+/// tracing::subscriber::with_default(recorder.clone(), || {
+///     tokio::runtime::Runtime::new().unwrap().block_on(async {
+///         let system = Fluxion::new("system", ());
+///         let greeter = system.add(Greeter).await.unwrap();
+///         greeter.send(Greet).await.unwrap();
+///     });
+/// });
+///
+/// let message_type = format!("message={:?}", std::any::type_name::<Greet>());
+/// assert_eq!(*recorder.0.lock().unwrap(), [
+///     "handle_message",
+///     "system=\"system\"",
+///     "actor=0",
+///     &message_type,
+///     "message=actor received message",
+///     "message=handler returned",
+/// ]);
+/// # }
+/// ```
+///
+/// Handlers run in the task that sent the message, so the `handle_message` span is a child of whichever span
+/// was current at the send. Instrumenting the sends made while serving a request is enough to correlate
+/// the actor's work with the request's span:
+///
+/// ```
+/// # #[cfg(feature = "tracing")] {
+/// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSender};
+/// # use std::sync::{Arc, Mutex, atomic::{AtomicU64, Ordering}};
+/// # use tracing::{span::{Attributes, Id, Record}, Event, Instrument, Metadata, Subscriber};
+/// # #[actor] struct Greeter;
+/// # #[message] struct Greet;
+/// # impl Handler<Greet> for Greeter {
+/// #     async fn handle_message<D: Delegate>(&self, _message: Greet, _context: &ActorContext<D>) {}
+/// # }
+/// /// Records the name of every span along with the name of its parent
+/// #[derive(Default)]
+/// struct Parents {
+///     next: AtomicU64,
+///     names: Mutex<Vec<&'static str>>,
+///     entered: Mutex<Vec<Id>>,
+///     spans: Arc<Mutex<Vec<(&'static str, Option<&'static str>)>>>,
+/// }
+///
+/// impl Subscriber for Parents {
+///     fn enabled(&self, _metadata: &Metadata<'_>) -> bool { true }
+///     fn new_span(&self, span: &Attributes<'_>) -> Id {
+///         let mut names = self.names.lock().unwrap();
+///         let parent = match span.parent() {
+///             Some(parent) => Some(parent.clone()),
+///             None if span.is_contextual() => self.entered.lock().unwrap().last().cloned(),
+///             None => None,
+///         };
+///         let parent = parent.map(|id| names[id.into_u64() as usize - 1]);
+///         self.spans.lock().unwrap().push((span.metadata().name(), parent));
+///
+///         names.push(span.metadata().name());
+///         Id::from_u64(self.next.fetch_add(1, Ordering::Relaxed) + 1)
+///     }
+///     fn record(&self, _span: &Id, _values: &Record<'_>) {}
+///     fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+///     fn event(&self, _event: &Event<'_>) {}
+///     fn enter(&self, span: &Id) { self.entered.lock().unwrap().push(span.clone()); }
+///     fn exit(&self, _span: &Id) { self.entered.lock().unwrap().pop(); }
+/// }
+///
+/// let subscriber = Parents::default();
+/// let spans = subscriber.spans.clone();
+/// tracing::subscriber::with_default(subscriber, || {
+///     tokio::runtime::Runtime::new().unwrap().block_on(async {
+///         let system = Fluxion::new("system", ());
+///         let greeter = system.add(Greeter).await.unwrap();
+///
+///         let request = tracing::info_span!("http_request");
+///         greeter.send(Greet).instrument(request).await.unwrap();
+///     });
+/// });
+///
+/// assert_eq!(*spans.lock().unwrap(), [("http_request", None), ("handle_message", Some("http_request"))]);
+/// # }
+/// ```
 pub trait Handler<M: Message>: Actor {
+    /// # [`TIME_BUDGET`]
+    /// How long handling a message of this type is expected to take, or [`None`] (the default) for no budget.
+    /// Handlers that take longer are reported to the system's [`Metrics::on_budget_exceeded`](crate::Metrics::on_budget_exceeded),
+    /// along with a warning if the `tracing` feature is enabled.
+    /// Handling times are measured with [`Timer::now`], so budgets are only checked on systems with a timer that can measure them.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, Metrics, MessageSender, Timer};
+    /// # use std::{future::Future, pin::Pin, sync::{Arc, Mutex}, time::{Duration, Instant}};
+    /// # struct StdTimer(Instant);
+    /// # impl Timer for StdTimer {
+    /// #     fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> { Box::pin(tokio::time::sleep(duration)) }
+    /// #     fn now(&self) -> Option<Duration> { Some(self.0.elapsed()) }
+    /// # }
+    /// #[actor] struct Database;
+    /// #[message] struct Query(Duration);
+    ///
+    /// impl Handler<Query> for Database {
+    ///     const TIME_BUDGET: Option<Duration> = Some(Duration::from_millis(20));
+    ///
+    ///     async fn handle_message<D: Delegate>(&self, message: Query, _context: &ActorContext<D>) {
+    ///         tokio::time::sleep(message.0).await;
+    ///     }
+    /// }
+    ///
+    /// struct SlowHandlers(Arc<Mutex<Vec<(Duration, Duration)>>>);
+    ///
+    /// impl Metrics for SlowHandlers {
+    ///     fn on_budget_exceeded(&self, _actor: u64, _message: &str, budget: Duration, elapsed: Duration) {
+    ///         self.0.lock().unwrap().push((budget, elapsed));
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let slow = Arc::new(Mutex::new(Vec::new()));
+    /// let system = Fluxion::new("system", ())
+    ///     .with_timer(StdTimer(Instant::now()))
+    ///     .with_metrics(SlowHandlers(slow.clone()));
+    /// let database = system.add(Database).await.unwrap();
+    ///
+    /// database.send(Query(Duration::ZERO)).await.unwrap();
+    /// assert!(slow.lock().unwrap().is_empty());
+    ///
+    /// database.send(Query(Duration::from_millis(50))).await.unwrap();
+    /// let [(budget, elapsed)] = slow.lock().unwrap()[..] else { panic!() };
+    /// assert_eq!(budget, Duration::from_millis(20));
+    /// assert!(elapsed >= Duration::from_millis(50));
+    /// # });
+    /// ```
+    const TIME_BUDGET: Option<Duration> = None;
+
     fn handle_message<D: Delegate>(&self, message: M, context: &ActorContext<D>) -> impl core::future::Future<Output = M::Result> + Send;
+
+    /// Checks a message before it is handled. If this returns an error, the message is never passed to
+    /// [`Handler::handle_message`], and the sender receives [`MessageSendError::Validation`](crate::MessageSendError::Validation) instead.
+    /// By default, every message is accepted.
+    ///
+    /// Validation is run by [`LocalRef`](crate::LocalRef) on every send, including sends through [`Fluxion::get`](crate::Fluxion::get)
+    /// and [`Pipe`](crate::Pipe), and sends made on behalf of a foreign system by a delegate using a local reference.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSendError, MessageSender, ValidationError};
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// static HANDLED: AtomicBool = AtomicBool::new(false);
+    ///
+    /// #[actor]
+    /// struct Accounts;
+    ///
+    /// #[message]
+    /// struct Withdraw(i64);
+    ///
+    /// impl Handler<Withdraw> for Accounts {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Withdraw, _context: &ActorContext<D>) {
+    ///         HANDLED.store(true, Ordering::Relaxed);
+    ///     }
+    ///
+    ///     fn validate(message: &Withdraw) -> Result<(), ValidationError> {
+    ///         if message.0 <= 0 {
+    ///             return Err(ValidationError::new("amount must be positive"));
+    ///         }
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let accounts = system.add(Accounts).await.unwrap();
+    ///
+    /// let Err(MessageSendError::Validation(e)) = accounts.send(Withdraw(-5)).await else { panic!() };
+    /// assert_eq!(e.reason(), "amount must be positive");
+    /// assert!(!HANDLED.load(Ordering::Relaxed));
+    ///
+    /// accounts.send(Withdraw(5)).await.unwrap();
+    /// assert!(HANDLED.load(Ordering::Relaxed));
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a [`ValidationError`] describing why the message was rejected.
+    fn validate(_message: &M) -> Result<(), ValidationError> {
+        Ok(())
+    }
+}
+
+/// # [`EmittingHandler`]
+/// A handler that responds to a message and emits follow-up messages to other local actors as a single step,
+/// such as an event-sourced actor that publishes the events produced by a command.
+///
+/// An [`EmittingHandler`] is not a [`Handler`] by itself. Instead, the actor's [`Handler`] implementation calls
+/// [`ActorContext::handle_emitting`], which keeps [`Handler::validate`] and [`Handler::TIME_BUDGET`] available to override.
+/// The response is returned first, and the emitted messages are then delivered in order by a task started by the system's [`Spawner`](crate::Spawner),
+/// so the sender does not wait for them. Emitted messages whose target does not exist, or fails to handle them, are discarded.
+///
+/// <div class = "warn">
+/// Systems without a spawner can not deliver messages after responding, so they deliver the emitted messages before the response is returned instead.
+/// An actor that emits messages to itself on such a system must not set an [`Actor::MAILBOX_CAPACITY`] of `Some(1)`,
+/// as its handler would wait for its own slot.
+/// </div>
+///
+/// ```
+/// # use fluxion::{actor, message, Actor, ActorContext, Delegate, EmittingHandler, Envelope, Fluxion, Handler, MessageSender, Spawner};
+/// # use std::{future::Future, pin::Pin, sync::atomic::{AtomicU64, Ordering}, time::Duration};
+/// # struct TokioSpawner;
+/// # impl Spawner for TokioSpawner {
+/// #     fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>) { tokio::spawn(future); }
+/// # }
+/// #[actor]
+/// struct Auditor {
+///     seen: AtomicU64,
+/// }
+///
+/// #[message]
+/// struct Audit(u64);
+///
+/// impl Handler<Audit> for Auditor {
+///     async fn handle_message<D: Delegate>(&self, message: Audit, _context: &ActorContext<D>) {
+///         self.seen.fetch_add(message.0, Ordering::Relaxed);
+///     }
+/// }
+///
+/// #[message(u64)]
+/// struct Count;
+///
+/// impl Handler<Count> for Auditor {
+///     async fn handle_message<D: Delegate>(&self, _message: Count, _context: &ActorContext<D>) -> u64 {
+///         self.seen.load(Ordering::Relaxed)
+///     }
+/// }
+///
+/// struct Ledger {
+///     auditor: u64,
+///     balance: AtomicU64,
+/// }
+///
+/// impl Actor for Ledger {
+///     type Error = ();
+///     // Emitting to itself doesn't wait for this handler to finish, so a single slot is enough
+///     const MAILBOX_CAPACITY: Option<usize> = Some(1);
+/// }
+///
+/// #[message(&'static str)]
+/// struct Transfer;
+///
+/// #[message]
+/// struct Credit(u64);
+///
+/// impl EmittingHandler<Transfer> for Ledger {
+///     async fn handle_emitting<D: Delegate>(&self, _message: Transfer, context: &ActorContext<D>) -> (&'static str, Vec<Envelope<D>>) {
+///         ("done", vec![
+///             Envelope::new::<Auditor, _>(self.auditor, Audit(1)),
+///             Envelope::new::<Auditor, _>(self.auditor, Audit(2)),
+///             Envelope::new::<Self, _>(context.get_id(), Credit(10)),
+///         ])
+///     }
+/// }
+///
+/// impl Handler<Transfer> for Ledger {
+///     async fn handle_message<D: Delegate>(&self, message: Transfer, context: &ActorContext<D>) -> &'static str {
+///         context.handle_emitting(self, message).await
+///     }
+/// }
+///
+/// impl Handler<Credit> for Ledger {
+///     async fn handle_message<D: Delegate>(&self, message: Credit, _context: &ActorContext<D>) {
+///         self.balance.fetch_add(message.0, Ordering::Relaxed);
+///     }
+/// }
+///
+/// #[message(u64)]
+/// struct Balance;
+///
+/// impl Handler<Balance> for Ledger {
+///     async fn handle_message<D: Delegate>(&self, _message: Balance, _context: &ActorContext<D>) -> u64 {
+///         self.balance.load(Ordering::Relaxed)
+///     }
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let system = Fluxion::new("system", ()).with_spawner(TokioSpawner);
+/// let auditor = system.add(Auditor { seen: AtomicU64::new(0) }).await.unwrap();
+/// let ledger = system.add(Ledger { auditor: auditor.get_id(), balance: AtomicU64::new(0) }).await.unwrap();
+///
+/// assert_eq!(ledger.send(Transfer).await.unwrap(), "done");
+///
+/// // The emitted messages are delivered after the response
+/// tokio::time::sleep(Duration::from_millis(50)).await;
+/// assert_eq!(auditor.send(Count).await.unwrap(), 3);
+/// assert_eq!(ledger.send(Balance).await.unwrap(), 10);
+/// # });
+/// ```
+pub trait EmittingHandler<M: Message>: Actor {
+    /// Handles the message, returning the response and the messages to emit
+    fn handle_emitting<D: Delegate>(&self, message: M, context: &ActorContext<D>) -> impl core::future::Future<Output = (M::Result, Vec<Envelope<D>>)> + Send;
+}
+
+/// Delivers an [`Envelope`]'s message using the given system
+type DeliverFn<D> = Box<dyn for<'a> FnOnce(&'a Fluxion<D>) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> + Send>;
+
+/// # [`Envelope`]
+/// A message addressed to a local actor, with the types of both erased.
+/// These are emitted by an [`EmittingHandler`].
+pub struct Envelope<D = ()> {
+    /// Delivers the message
+    deliver: DeliverFn<D>,
+}
+
+impl<D: Delegate> Envelope<D> {
+    /// # [`Envelope::new`]
+    /// Creates an envelope containing a message for the local actor with the given id
+    pub fn new<A: Handler<M>, M: Message>(target: u64, message: M) -> Self {
+        Self {
+            deliver: Box::new(move |system| Box::pin(async move {
+                if let Ok(actor) = system.get_local::<A>(target).await {
+                    let _ = actor.send(message).await;
+                }
+            })),
+        }
+    }
 }
 
 
@@ -78,8 +921,64 @@ impl<R: Actor, D: Delegate> slacktor::Actor for ActorWrapper<R, D> {
 
 impl<R: Handler<M>, M: Message, D: Delegate> slacktor::actor::Handler<M> for ActorWrapper<R, D> {
     #[inline]
-    fn handle_message(&self, message: M) -> impl core::future::Future<Output = <M as Message>::Result> + Send {
-        self.0.handle_message(message, &self.1)
+    async fn handle_message(&self, message: M) -> <M as Message>::Result {
+        let interceptors = self.1.system.interceptors();
+        let message_name = core::any::type_name::<M>();
+
+        // Note the start time, if the handling time will be reported or checked against the budget
+        let metrics = self.1.system.metrics();
+        let timer = self.1.system.timer().filter(|_| metrics.is_some() || R::TIME_BUDGET.is_some());
+        let start = timer.and_then(Timer::now);
+
+        for interceptor in interceptors {
+            interceptor.before(self.1.id, message_name);
+        }
+
+        #[cfg(feature = "metrics")]
+        let in_flight = self.1.counters.begin(metrics.map(AsRef::as_ref), self.1.id, message_name);
+
+        // Run the handler, within a span for the message if tracing is enabled
+        #[cfg(not(feature = "tracing"))]
+        let res = self.0.handle_message(message, &self.1).await;
+        #[cfg(feature = "tracing")]
+        let res = {
+            use tracing::Instrument;
+
+            let span = tracing::debug_span!("handle_message", system = self.1.system.get_id(), actor = self.1.id, message = message_name);
+            async {
+                tracing::trace!("actor received message");
+                let res = self.0.handle_message(message, &self.1).await;
+                tracing::trace!("handler returned");
+                res
+            }.instrument(span).await
+        };
+
+        #[cfg(feature = "metrics")]
+        in_flight.finish();
+
+        let elapsed = timer.and_then(Timer::now)
+            .zip(start)
+            .map(|(end, start)| end.saturating_sub(start));
+
+        if let Some(metrics) = metrics {
+            metrics.on_message_handled(self.1.id, message_name, elapsed);
+        }
+
+        // Report the handler if it went over its budget
+        if let Some((budget, elapsed)) = R::TIME_BUDGET.zip(elapsed).filter(|(budget, elapsed)| elapsed > budget) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(system = self.1.system.get_id(), actor = self.1.id, message = message_name, ?budget, ?elapsed, "handler exceeded its time budget");
+
+            if let Some(metrics) = metrics {
+                metrics.on_budget_exceeded(self.1.id, message_name, budget, elapsed);
+            }
+        }
+
+        for interceptor in interceptors.iter().rev() {
+            interceptor.after(self.1.id, message_name);
+        }
+
+        res
     }
 }
 
@@ -0,0 +1,90 @@
+//! # Codecs
+//! Foreign messages must be converted to bytes before a [`Delegate`](crate::Delegate) can send them to another system.
+//! This module provides the [`Codec`] trait, which delegates can be generic over to choose how that is done,
+//! along with codecs for some common formats behind their respective features.
+
+use alloc::vec::Vec;
+use serde::{de::DeserializeOwned, Serialize};
+
+
+/// # [`Codec`]
+/// Encodes values to bytes and decodes them back, for sending foreign messages and their responses between systems.
+/// Both systems on either end of a delegate must use the same codec.
+///
+/// ```
+/// # use fluxion::Codec;
+/// # use serde::{de::DeserializeOwned, Serialize};
+/// struct JsonCodec;
+///
+/// impl Codec for JsonCodec {
+///     type Error = serde_json::Error;
+///
+///     fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+///         serde_json::to_vec(value)
+///     }
+///
+///     fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+///         serde_json::from_slice(bytes)
+///     }
+/// }
+///
+/// let bytes = JsonCodec.encode(&(1, "two")).unwrap();
+/// assert_eq!(bytes, br#"[1,"two"]"#);
+/// assert_eq!(JsonCodec.decode::<(u8, String)>(&bytes).unwrap(), (1, String::from("two")));
+/// ```
+pub trait Codec: Send + Sync + 'static {
+    /// The error returned when a value fails to be encoded or decoded
+    type Error: core::fmt::Debug + core::fmt::Display;
+
+    /// # [`Codec::encode`]
+    /// Encodes the given value to bytes.
+    ///
+    /// # Errors
+    /// Returns an error if the value can not be represented by this codec.
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, Self::Error>;
+
+    /// # [`Codec::decode`]
+    /// Decodes a value from the given bytes.
+    ///
+    /// # Errors
+    /// Returns an error if the bytes are not a valid encoding of a `T`.
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, Self::Error>;
+}
+
+/// # [`BincodeCodec`]
+/// A [`Codec`] that uses [`bincode`]'s default configuration.
+#[cfg(feature = "bincode")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BincodeCodec;
+
+#[cfg(feature = "bincode")]
+impl Codec for BincodeCodec {
+    type Error = bincode::Error;
+
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+        bincode::serialize(value)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        bincode::deserialize(bytes)
+    }
+}
+
+/// # [`PostcardCodec`]
+/// A [`Codec`] that uses [`postcard`], which is compact and does not require `std`.
+#[cfg(feature = "postcard")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PostcardCodec;
+
+#[cfg(feature = "postcard")]
+impl Codec for PostcardCodec {
+    type Error = postcard::Error;
+
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+        postcard::to_allocvec(value)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        postcard::from_bytes(bytes)
+    }
+}
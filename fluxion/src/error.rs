@@ -0,0 +1,161 @@
+//! # Errors
+//! This module contains the errors returned by Fluxion's actor management functions.
+//! Errors that occur while sending messages are instead represented by [`MessageSendError`](crate::MessageSendError).
+
+
+/// # [`SystemError`]
+/// An error that may be returned when managing actors on a [`Fluxion`](crate::Fluxion) system,
+/// such as when retrieving a reference to an actor, or adding one.
+///
+/// ```
+/// # use fluxion::{actor, message, ActorContext, AddError, Delegate, Fluxion, Handler, MessageSender, SystemError};
+/// # use std::sync::Arc;
+/// # #[actor] struct Worker;
+/// # #[actor] struct Other;
+/// #[message(Option<SystemError>)]
+/// struct Later;
+///
+/// impl Handler<Later> for Worker {
+///     async fn handle_message<D: Delegate>(&self, _message: Later, context: &ActorContext<D>) -> Option<SystemError> {
+///         let target = Arc::new(context.self_ref::<Self>().await?);
+///         context.schedule(target, Later, core::time::Duration::ZERO).err()
+///     }
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let system = Fluxion::new("system", ()).with_max_actors(2);
+/// let worker = system.add(Worker).await.unwrap();
+/// let id = worker.get_id();
+///
+/// assert!(matches!(system.get_local::<Worker>(id + 1).await, Err(SystemError::NotFound)));
+/// assert!(matches!(system.get_local::<Other>(id).await, Err(SystemError::TypeMismatch { .. })));
+///
+/// // The system has no timer or spawner to schedule with
+/// assert_eq!(worker.send(Later).await.unwrap(), Some(SystemError::NoScheduler));
+///
+/// system.add_named("worker", Worker).await.unwrap();
+/// assert!(matches!(system.add(Worker).await, Err(AddError::System(SystemError::AtCapacity { limit: 2 }))));
+/// system.kill::<Worker>(id).await;
+/// assert_eq!(system.add_named("worker", Worker).await, Err(AddError::System(SystemError::NameTaken)));
+///
+/// system.shutdown().await;
+/// assert!(matches!(system.get_local::<Worker>(id).await, Err(SystemError::ShuttingDown)));
+/// # });
+/// ```
+///
+/// `SystemError::Unsupported` and `SystemError::Disconnected` are only returned for foreign actors,
+/// as shown by `Delegate::supports` and `Delegate::is_connected`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SystemError {
+    /// No actor exists with the given identifier.
+    NotFound,
+    /// An actor exists with the given id, but it is of a different type than the one requested.
+    /// Contains the type names of both the requested actor and the actor that actually exists.
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The system already contains the maximum number of actors set by [`Fluxion::with_max_actors`](crate::Fluxion::with_max_actors).
+    AtCapacity {
+        limit: usize,
+    },
+    /// Another actor already has the requested name.
+    NameTaken,
+    /// A background task or timeout was requested, but the system has no [`Timer`](crate::Timer) or no [`Spawner`](crate::Spawner).
+    /// These are set with [`Fluxion::with_timer`](crate::Fluxion::with_timer) and [`Fluxion::with_spawner`](crate::Fluxion::with_spawner).
+    NoScheduler,
+    /// The system has been shut down, and can no longer be used.
+    ShuttingDown,
+    /// The delegate reported that the foreign system does not support the requested message.
+    /// Contains the message's id.
+    #[cfg(feature = "foreign")]
+    Unsupported {
+        message: &'static str,
+    },
+    /// The delegate reported that its transport is not connected.
+    #[cfg(feature = "foreign")]
+    Disconnected,
+}
+
+impl core::fmt::Display for SystemError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SystemError::NotFound => write!(f, "SystemError: actor not found"),
+            SystemError::TypeMismatch { expected, found } => write!(f, "SystemError: expected an actor of type {expected}, but found {found}"),
+            SystemError::AtCapacity { limit } => write!(f, "SystemError: the system is limited to {limit} actors"),
+            SystemError::NameTaken => write!(f, "SystemError: an actor with the given name already exists"),
+            SystemError::NoScheduler => write!(f, "SystemError: the system has no timer or no spawner"),
+            SystemError::ShuttingDown => write!(f, "SystemError: the system has been shut down"),
+            #[cfg(feature = "foreign")]
+            SystemError::Unsupported { message } => write!(f, "SystemError: message {message} is not supported by the foreign system"),
+            #[cfg(feature = "foreign")]
+            SystemError::Disconnected => write!(f, "SystemError: the delegate is not connected"),
+        }
+    }
+}
+
+impl core::error::Error for SystemError {}
+
+/// # [`GetError`]
+/// The error previously returned when retrieving a reference to an actor.
+/// It is now an alias of [`SystemError`], which has the same variants, so existing matches keep compiling.
+///
+/// | Before | After |
+/// |--------|-------|
+/// | `GetError` | [`SystemError`] |
+/// | `GetError::NotFound` | [`SystemError::NotFound`] |
+/// | `GetError::TypeMismatch { .. }` | [`SystemError::TypeMismatch`] |
+/// | `GetError::Unsupported { .. }` | `SystemError::Unsupported` |
+/// | `AddError::LimitReached { limit }` | [`AddError::System`]`(`[`SystemError::AtCapacity`]` { limit })` |
+///
+/// ```
+/// # use fluxion::{actor, Fluxion, SystemError};
+/// # #[actor] struct Worker;
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let system = Fluxion::new("system", ());
+///
+/// #[allow(deprecated)]
+/// let error: Option<fluxion::GetError> = system.get_local::<Worker>(0).await.err();
+/// assert_eq!(error, Some(SystemError::NotFound));
+/// # });
+/// ```
+#[deprecated(note = "use `SystemError` instead")]
+pub type GetError = SystemError;
+
+
+/// # [`AddError`]
+/// An error that may be returned when adding an actor to the system.
+/// `E` is the actor's [`Actor::Error`](crate::Actor::Error) type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AddError<E> {
+    /// The actor failed to initialize.
+    Initialize(E),
+    /// The system refused to add the actor.
+    System(SystemError),
+}
+
+impl<E> From<SystemError> for AddError<E> {
+    fn from(e: SystemError) -> Self {
+        Self::System(e)
+    }
+}
+
+impl<E: core::fmt::Debug> core::fmt::Display for AddError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AddError::Initialize(e) => write!(f, "AddError: actor failed to initialize: {e:?}"),
+            AddError::System(e) => write!(f, "AddError: {e}"),
+        }
+    }
+}
+
+impl<E: core::fmt::Debug + 'static> core::error::Error for AddError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            AddError::Initialize(_) => None,
+            AddError::System(e) => Some(e),
+        }
+    }
+}
@@ -1,33 +1,270 @@
 
-use alloc::sync::Arc;
-use maitake_sync::RwLock;
+use alloc::{boxed::Box, sync::Arc};
+use core::{any::{Any, TypeId}, future::Future, pin::Pin, sync::atomic::{AtomicBool, AtomicUsize, Ordering}, task::Poll};
+use maitake_sync::{spin::Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard, Semaphore, WaitQueue};
 use slacktor::Slacktor;
 
-use crate::{Actor, ActorContext, ActorWrapper, Delegate, Handler, Identifier, IndeterminateMessage, LocalRef, MessageSender};
-use alloc::string::String;
-use alloc::collections::BTreeMap;
+use crate::{timer::with_deadline, Actor, ActorContext, ActorWrapper, AddError, AnyLocalRef, Delegate, Handler, Identifier, IndeterminateMessage, Interceptor, Metrics, LocalRef, Message, MessageSender, Pipe, Spawner, SystemError, Timer};
+#[cfg(feature = "foreign")]
+use crate::{SenderCache, SenderCacheKey, TimedSender};
+use alloc::{string::String, vec::Vec};
+use alloc::collections::{BTreeMap, BTreeSet};
 
 
+/// # [`ActorEntry`]
+/// Bookkeeping that is kept for every actor on the system.
+struct ActorEntry {
+    /// The key of the actor's slot in its shard's slab
+    key: usize,
+    /// The actor's type name, as returned by [`core::any::type_name`]
+    type_name: &'static str,
+    /// The actor's type id, which identifies its type exactly, unlike its name
+    type_id: TypeId,
+    /// Whether the actor has been marked as ready
+    ready: bool,
+    /// Kills the actor without knowing its type
+    kill: KillFn,
+    /// Deinitializes the actor without knowing its type, during shutdown
+    deinit: DeinitFn,
+    /// Limits the number of messages in flight to the actor, if it has a [`Actor::MAILBOX_CAPACITY`]
+    inflight: Option<Arc<Semaphore>>,
+    /// The actor's [`Actor::SHUTDOWN_PRIORITY`]
+    shutdown_priority: i32,
+    /// The identity the actor was added with by [`Fluxion::add_deduplicated`], if any
+    identity: Option<String>,
+    /// The id of the actor that added this actor with [`ActorContext::add_child`], if any
+    parent: Option<u64>,
+    /// The actor's counters, shared with its context
+    #[cfg(feature = "metrics")]
+    counters: Arc<crate::metrics::ActorCounters>,
+}
+
+/// Kills the actor in the given slot of a slacktor shard.
+/// This is stored in each actor's [`ActorEntry`], so that actors can be killed by id alone.
+type KillFn = for<'a> fn(&'a mut Slacktor, usize) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// Senders registered with [`TestHarness::stub`](crate::test_util::TestHarness::stub), keyed by actor id and message type.
+/// Each is an `Arc<dyn MessageSender<M>>` for the message type in its key.
+#[cfg(feature = "test-util")]
+type StubTable = BTreeMap<(u64, TypeId), Arc<dyn Any + Send + Sync>>;
+
+/// Returns a future that deinitializes the actor in the given slot of a slacktor shard, without removing it from the shard.
+/// The future does not borrow the shard, so that many actors can be deinitialized at once during [`Fluxion::shutdown`].
+type DeinitFn = fn(&Slacktor, usize) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// The [`DeinitFn`] for actors of type `A`
+fn deinit_actor<A: Actor, D: Delegate>(system: &Slacktor, key: usize) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+    let handle = system.get::<ActorWrapper<A, D>>(key).cloned();
+
+    Box::pin(async move {
+        if let Some(handle) = handle {
+            handle.kill().await;
+        }
+    })
+}
+
+/// Polls the given futures concurrently until every one has completed, returning their outputs in order
+async fn join_all<F: Future>(futures: impl IntoIterator<Item = F>) -> Vec<F::Output> {
+    let mut futures = futures.into_iter().map(|future| Some(Box::pin(future))).collect::<Vec<_>>();
+    let mut outputs = futures.iter().map(|_| None).collect::<Vec<_>>();
+
+    core::future::poll_fn(|cx| {
+        let mut pending = false;
+        for (slot, output) in futures.iter_mut().zip(&mut outputs) {
+            let Some(future) = slot else { continue };
+
+            match future.as_mut().poll(cx) {
+                Poll::Ready(value) => {
+                    *output = Some(value);
+                    *slot = None;
+                },
+                Poll::Pending => pending = true,
+            }
+        }
+
+        if pending { Poll::Pending } else { Poll::Ready(()) }
+    }).await;
+
+    outputs.into_iter().flatten().collect()
+}
+
+/// The [`KillFn`] for actors of type `A`
+fn kill_actor<A: Actor, D: Delegate>(system: &mut Slacktor, key: usize) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+    Box::pin(async move {
+        system.kill::<ActorWrapper<A, D>>(key).await;
+    })
+}
+
+impl ActorTable {
+    /// Removes the entries of every descendant of the given actors, returning them with the deepest descendants first
+    fn take_descendants(&mut self, ids: &[u64]) -> Vec<(u64, ActorEntry)> {
+        let mut descendants = Vec::new();
+        let mut parents = ids.to_vec();
+
+        while !parents.is_empty() {
+            let children = self.entries.iter()
+                .filter(|(_, entry)| entry.parent.is_some_and(|parent| parents.contains(&parent)))
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+
+            for id in &children {
+                if let Some(entry) = self.entries.remove(id) {
+                    descendants.push((*id, entry));
+                }
+            }
+
+            parents = children;
+        }
+
+        descendants.reverse();
+        descendants
+    }
+
+    /// Returns the ids of the actors added by the given actor with [`ActorContext::add_child`]
+    fn children(&self, id: u64) -> Vec<u64> {
+        self.entries.iter()
+            .filter(|(_, entry)| entry.parent == Some(id))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+}
+
+/// # [`ActorInfo`]
+/// Metadata about an actor on the system, passed to the predicate of [`Fluxion::kill_where`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ActorInfo<'a> {
+    /// The actor's id
+    pub id: u64,
+    /// The actor's type name, as returned by [`core::any::type_name`]
+    pub type_name: &'static str,
+    /// The name the actor was added with by [`Fluxion::add_named`], if any
+    pub name: Option<&'a str>,
+}
+
+/// # [`ActorTable`]
+/// Maps actor ids to their [`ActorEntry`].
+#[derive(Default)]
+struct ActorTable {
+    /// The entry of every actor on the system
+    entries: BTreeMap<u64, ActorEntry>,
+    /// The id that will be assigned to the next actor.
+    /// Ids are assigned from this counter and never reused, so the sequence of ids assigned
+    /// is the same across runs regardless of the order in which actors are added and killed.
+    #[cfg(feature = "deterministic-ids")]
+    next_id: u64,
+}
+
 
 /// # [`Fluxion`]
-/// Contains the core actor management functionality of fluxion
-pub struct Fluxion<D> {
-    /// The underlying slacktor instance.
-    /// This is wrapped in an [`Arc`] and [`RwLock`] to allow concurrent access from different tasks.
-    /// The [`RwLock`] is used instead of a mutex because it can be assumed that actor references
-    /// will be retrieved more often than actors are created.
-    slacktor: Arc<RwLock<Slacktor>>,
+/// Contains the core actor management functionality of fluxion.
+/// The delegate defaults to `()`, so systems that only use local actors can be named as just `Fluxion`,
+/// as can their [`ActorContext`], [`LocalRef`], and [`AnyLocalRef`].
+///
+/// The `with_*` methods configure a system by value, returning it with the setting changed.
+/// Only the returned system and clones made from it afterwards see the change. Earlier clones, including those held by
+/// the contexts of actors that were already added, keep the old setting, so these methods are usually chained onto [`Fluxion::new`].
+pub struct Fluxion<D = ()> {
+    /// The underlying slacktor instances, each of which holds a shard of the system's actors.
+    /// An actor lives in the shard given by its id modulo the number of shards, so actors on different
+    /// shards can be added, killed, and retrieved concurrently. Each shard is wrapped in an [`RwLock`]
+    /// instead of a mutex because it can be assumed that actor references will be retrieved more often than actors are created.
+    /// When several shards are needed, they are always locked in ascending order.
+    shards: Arc<[RwLock<Slacktor>]>,
+    /// The shard that the next actor without a parent or identity will be added to, modulo the number of shards
+    next_shard: Arc<AtomicUsize>,
     /// A mapping of string actor names to their slacktor ids.
     actor_ids: Arc<RwLock<BTreeMap<String, u64>>>,
     /// The identifier of this system as a string
     system_id: Arc<str>,
     /// The foreign delegate of this system
     delegate: Arc<D>,
+    /// Bookkeeping for every actor on the system.
+    /// When both are needed, this is always locked after the shards.
+    actors: Arc<RwLock<ActorTable>>,
+    /// Woken whenever an actor becomes ready or is assigned a name,
+    /// and whenever an attempt to add a named actor finishes
+    readiness: Arc<WaitQueue>,
+    /// Woken whenever actors are killed
+    stopped: Arc<WaitQueue>,
+    /// The names of actors that are currently being added by [`Fluxion::add_named`] or [`Fluxion::get_or_spawn_named`].
+    /// This is a spinlock, as it is never held across an await point.
+    spawning: Arc<Mutex<BTreeSet<String>>>,
+    /// A cache of senders retrieved from the delegate, if enabled
+    #[cfg(feature = "foreign")]
+    foreign_cache: Option<Arc<SenderCache>>,
+    /// The maximum number of actors that may exist on the system at once, if limited
+    max_actors: Option<usize>,
+    /// The timer set with [`Fluxion::with_timer`], if any
+    timer: Option<Arc<dyn Timer>>,
+    /// The spawner set with [`Fluxion::with_spawner`], if any
+    spawner: Option<Arc<dyn Spawner>>,
+    /// Shared state set with [`Fluxion::with_state`], keyed by its type
+    state: Arc<BTreeMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+    /// Interceptors registered with [`Fluxion::with_interceptor`], in registration order
+    interceptors: Arc<[Arc<dyn Interceptor>]>,
+    /// The metrics installed with [`Fluxion::with_metrics`], if any
+    metrics: Option<Arc<dyn Metrics>>,
+    /// Child systems created by [`Fluxion::fork_linked`], which are shut down along with this system.
+    /// This is a spinlock, as it is never held across an await point.
+    linked: Arc<Mutex<Vec<Fluxion<D>>>>,
+    /// Senders registered with [`TestHarness::stub`](crate::test_util::TestHarness::stub).
+    /// This is a spinlock, as it is never held across an await point.
+    #[cfg(feature = "test-util")]
+    stubs: Arc<Mutex<StubTable>>,
+    /// Set once the system has been shut down.
+    /// This is only set while every shard is locked as write, so it can't change while any shard is locked.
+    shut_down: Arc<AtomicBool>,
 }
 
 impl<D> Clone for Fluxion<D> {
     fn clone(&self) -> Self {
-        Self { slacktor: self.slacktor.clone(), system_id: self.system_id.clone(), delegate: self.delegate.clone(), actor_ids: self.actor_ids.clone() }
+        Self {
+            shards: self.shards.clone(),
+            next_shard: self.next_shard.clone(),
+            system_id: self.system_id.clone(),
+            delegate: self.delegate.clone(),
+            actor_ids: self.actor_ids.clone(),
+            actors: self.actors.clone(),
+            readiness: self.readiness.clone(),
+            stopped: self.stopped.clone(),
+            spawning: self.spawning.clone(),
+            #[cfg(feature = "foreign")]
+            foreign_cache: self.foreign_cache.clone(),
+            max_actors: self.max_actors,
+            timer: self.timer.clone(),
+            spawner: self.spawner.clone(),
+            state: self.state.clone(),
+            interceptors: self.interceptors.clone(),
+            metrics: self.metrics.clone(),
+            linked: self.linked.clone(),
+            #[cfg(feature = "test-util")]
+            stubs: self.stubs.clone(),
+            shut_down: self.shut_down.clone(),
+        }
+    }
+}
+
+/// A claim on the name of an actor being added by [`Fluxion::add_named`] or [`Fluxion::get_or_spawn_named`].
+/// The claim is released when dropped, waking any tasks waiting for the actor.
+struct NameClaim<'a, D> {
+    system: &'a Fluxion<D>,
+    name: &'a str,
+}
+
+impl<'a, D> NameClaim<'a, D> {
+    /// Claims the given name, returning [`None`] if it has already been claimed
+    fn new(system: &'a Fluxion<D>, name: &'a str) -> Option<Self> {
+        let claimed = system.spawning.lock().insert(String::from(name));
+
+        claimed.then(|| Self { system, name })
+    }
+}
+
+impl<D> Drop for NameClaim<'_, D> {
+    fn drop(&mut self) {
+        self.system.spawning.lock().remove(self.name);
+        self.system.readiness.wake_all();
     }
 }
 
@@ -36,14 +273,365 @@ impl<D: Delegate> Fluxion<D> {
     /// Creates a new [`Fluxion`] instance with the given system id and delegate
     #[must_use]
     pub fn new(id: &str, delegate: D) -> Self {
+        Self::new_sharded(id, delegate, 1)
+    }
+
+    /// # [`Fluxion::new_sharded`]
+    /// Creates a new [`Fluxion`] instance with the given system id and delegate, which stores its actors in the given number of shards.
+    /// Each shard is locked separately, so adding, killing, and retrieving actors on different shards does not contend.
+    /// Operations that involve every actor, such as [`Fluxion::kill_where`] and [`Fluxion::shutdown`], lock every shard.
+    /// [`Fluxion::new`] uses a single shard. At least one shard is always used.
+    ///
+    /// An actor's shard is its id modulo the number of shards. Actors added with [`ActorContext::add_child`]
+    /// are stored on their parent's shard, and actors added with [`Fluxion::add_deduplicated`] are stored on a shard chosen by their identity.
+    /// Other actors are spread across the shards in turn.
+    ///
+    /// ```
+    /// # use fluxion::{actor, Fluxion};
+    /// # #[actor] struct Worker;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new_sharded("system", (), 4);
+    ///
+    /// let mut ids = Vec::new();
+    /// for _ in 0..8 {
+    ///     ids.push(system.add_id(Worker).await.unwrap());
+    /// }
+    ///
+    /// // Every shard is used
+    /// let mut shards = ids.iter().map(|id| id % 4).collect::<Vec<_>>();
+    /// shards.sort();
+    /// shards.dedup();
+    /// assert_eq!(shards, [0, 1, 2, 3]);
+    ///
+    /// assert!(system.kill::<Worker>(ids[5]).await);
+    /// assert!(system.get_local::<Worker>(ids[6]).await.is_ok());
+    /// assert_eq!(system.actor_count().await, 7);
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn new_sharded(id: &str, delegate: D, shards: usize) -> Self {
+        Self::with_shared_delegate(id, Arc::new(delegate), shards)
+    }
+
+    /// Creates a new [`Fluxion`] instance with the given system id, number of shards, and a delegate that may be shared with other systems
+    fn with_shared_delegate(id: &str, delegate: Arc<D>, shards: usize) -> Self {
         Self {
-            slacktor: Arc::new(RwLock::new(Slacktor::new())),
+            shards: (0..shards.max(1)).map(|_| RwLock::new(Slacktor::new())).collect(),
+            next_shard: Arc::default(),
             system_id: id.into(),
-            delegate: Arc::new(delegate),
+            delegate,
             actor_ids: Arc::default(),
+            actors: Arc::default(),
+            readiness: Arc::new(WaitQueue::new()),
+            stopped: Arc::new(WaitQueue::new()),
+            spawning: Arc::default(),
+            #[cfg(feature = "foreign")]
+            foreign_cache: None,
+            max_actors: None,
+            timer: None,
+            spawner: None,
+            state: Arc::default(),
+            interceptors: Arc::new([]),
+            metrics: None,
+            linked: Arc::default(),
+            #[cfg(feature = "test-util")]
+            stubs: Arc::default(),
+            shut_down: Arc::default(),
+        }
+    }
+
+    /// # [`Fluxion::fork`]
+    /// Creates a new, isolated system with the given id, which shares this system's delegate and has as many shards.
+    /// The child has its own actors and names, and actors on one system can not be retrieved locally from the other.
+    /// Foreign actors are still routed through the shared delegate, so both systems resolve them consistently.
+    ///
+    /// The child also shares this system's timer, spawner, shared state, interceptors, and metrics, but starts with no actor limit or foreign cache.
+    /// These can be set on the child using the usual `with_*` methods.
+    /// The lifecycles of the two systems are independent. Use [`Fluxion::fork_linked`] to shut the child down along with this system.
+    ///
+    /// ```
+    /// # use fluxion::{actor, Fluxion, SystemError};
+    /// # #[actor] struct Tenant;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let child = system.fork("tenant_a");
+    ///
+    /// system.add_named("worker", Tenant).await.unwrap();
+    /// child.add_named("worker", Tenant).await.unwrap();
+    /// child.add(Tenant).await.unwrap();
+    ///
+    /// assert_eq!(system.actor_count().await, 1);
+    /// assert_eq!(child.actor_count().await, 2);
+    /// assert_eq!(child.get_id(), "tenant_a");
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn fork(&self, child_id: &str) -> Self {
+        Self {
+            timer: self.timer.clone(),
+            spawner: self.spawner.clone(),
+            state: self.state.clone(),
+            interceptors: self.interceptors.clone(),
+            metrics: self.metrics.clone(),
+            ..Self::with_shared_delegate(child_id, self.delegate.clone(), self.shards.len())
         }
     }
 
+    /// # [`Fluxion::fork_linked`]
+    /// Creates a new, isolated system as if by [`Fluxion::fork`], which is shut down whenever this system is.
+    /// Linked children are shut down after this system's own actors.
+    ///
+    /// This system only keeps a child for as long as it may need shutting down. Children that have been shut down,
+    /// or that are no longer referenced by anything else, are forgotten whenever another child is linked or this system is shut down.
+    /// A child whose actors are still alive is always kept, as every actor references its system.
+    ///
+    /// ```
+    /// # use fluxion::{actor, Actor, Fluxion};
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// # #[actor] struct Tenant;
+    /// static STOPPED: AtomicBool = AtomicBool::new(false);
+    ///
+    /// struct Session;
+    ///
+    /// impl Actor for Session {
+    ///     type Error = ();
+    ///
+    ///     async fn deinitialize(&self) {
+    ///         STOPPED.store(true, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let child = system.fork_linked("tenant_a");
+    /// child.add(Tenant).await.unwrap();
+    ///
+    /// // This child is only referenced by its actor, so it is still shut down along with the system
+    /// system.fork_linked("tenant_b").add(Session).await.unwrap();
+    ///
+    /// system.shutdown().await;
+    /// assert_eq!(child.actor_count().await, 0);
+    /// assert!(STOPPED.load(Ordering::Relaxed));
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn fork_linked(&self, child_id: &str) -> Self {
+        let child = self.fork(child_id);
+
+        let mut linked = self.linked.lock();
+        Self::prune_linked(&mut linked);
+        linked.push(child.clone());
+        drop(linked);
+
+        child
+    }
+
+    /// Forgets the linked children that have been shut down, or that are no longer referenced by anything but the given list.
+    /// An unreferenced child has no actors, as each actor's context holds a clone of its system, so it has nothing left to shut down.
+    fn prune_linked(linked: &mut Vec<Fluxion<D>>) {
+        linked.retain(|child| !child.is_shutdown() && Arc::strong_count(&child.shut_down) > 1);
+    }
+
+    /// # [`Fluxion::with_timer`]
+    /// Sets the [`Timer`] used by the system and its actors to measure time, such as for timeouts.
+    /// References carry the timer they were retrieved with, so references retrieved before it is set
+    /// fail [`MessageSender::request_timeout`] with [`MessageSendError::NoTimer`](crate::MessageSendError::NoTimer).
+    #[must_use]
+    pub fn with_timer(mut self, timer: impl Timer) -> Self {
+        self.timer = Some(Arc::new(timer));
+        self
+    }
+
+    /// # [`Fluxion::timer`]
+    /// Returns the [`Timer`] set with [`Fluxion::with_timer`], if any.
+    #[must_use]
+    pub fn timer(&self) -> Option<&dyn Timer> {
+        self.timer.as_deref()
+    }
+
+    /// # [`Fluxion::with_spawner`]
+    /// Sets the [`Spawner`] used by the system and its actors to run background tasks, such as scheduled messages.
+    #[must_use]
+    pub fn with_spawner(mut self, spawner: impl Spawner) -> Self {
+        self.spawner = Some(Arc::new(spawner));
+        self
+    }
+
+    /// # [`Fluxion::spawner`]
+    /// Returns the [`Spawner`] set with [`Fluxion::with_spawner`], if any.
+    #[must_use]
+    pub fn spawner(&self) -> Option<&dyn Spawner> {
+        self.spawner.as_deref()
+    }
+
+    /// Returns the system's timer and spawner, or [`None`] if either is not set
+    pub(crate) fn scheduler(&self) -> Option<(Arc<dyn Timer>, &dyn Spawner)> {
+        Some((self.timer.clone()?, self.spawner.as_deref()?))
+    }
+
+    /// # [`Fluxion::with_state`]
+    /// Adds a value to the system's shared state, which is readable by every actor via [`ActorContext::state`].
+    /// The state holds at most one value of each type, so adding a value of a type that is already present replaces it.
+    /// Actors that were already added keep the state as it was when they were added, so values that need to change should use interior mutability.
+    #[must_use]
+    pub fn with_state<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+        let mut state = (*self.state).clone();
+        state.insert(TypeId::of::<T>(), Arc::new(value));
+        self.state = Arc::new(state);
+        self
+    }
+
+    /// # [`Fluxion::state`]
+    /// Retrieves the value of type `T` from the system's shared state,
+    /// or [`None`] if no such value was added with [`Fluxion::with_state`].
+    #[must_use]
+    pub fn state<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.state.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// # [`Fluxion::with_interceptor`]
+    /// Registers an [`Interceptor`], which is called around every message handled by an actor on the system.
+    /// Interceptors compose in registration order. See [`Interceptor`] for details.
+    /// Messages handled by actors that were already added are not intercepted.
+    #[must_use]
+    pub fn with_interceptor(mut self, interceptor: impl Interceptor) -> Self {
+        let mut interceptors = self.interceptors.to_vec();
+        interceptors.push(Arc::new(interceptor));
+        self.interceptors = interceptors.into();
+        self
+    }
+
+    /// Returns the ids of the actors added by the actor with the given id with [`ActorContext::add_child`]
+    pub(crate) async fn children(&self, id: u64) -> Vec<u64> {
+        self.actors.read().await.children(id)
+    }
+
+    /// Returns the interceptors registered with [`Fluxion::with_interceptor`], in registration order
+    pub(crate) fn interceptors(&self) -> &[Arc<dyn Interceptor>] {
+        &self.interceptors
+    }
+
+    /// # [`Fluxion::with_metrics`]
+    /// Installs a [`Metrics`] implementation, which is notified about the messages sent to and handled by actors on the system.
+    /// Handling times are measured using the system's [`Timer`], if it supports [`Timer::now`].
+    /// This replaces any previously installed metrics.
+    /// Actors that were already added, and references that were already retrieved, do not report to them.
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: impl Metrics) -> Self {
+        self.metrics = Some(Arc::new(metrics));
+        self
+    }
+
+    /// Returns the metrics installed with [`Fluxion::with_metrics`], if any
+    pub(crate) fn metrics(&self) -> Option<&Arc<dyn Metrics>> {
+        self.metrics.as_ref()
+    }
+
+    /// # [`Fluxion::with_max_actors`]
+    /// Limits the number of actors that may exist on the system at once.
+    /// Once the limit is reached, adding an actor fails with [`SystemError::AtCapacity`]
+    /// until another actor is killed. No memory is reserved for the actors up front.
+    /// Actors that were already added count towards the limit, but can still add children through their contexts without being limited.
+    ///
+    /// ```
+    /// # use fluxion::{actor, AddError, Fluxion, SystemError};
+    /// # #[actor] struct Tenant;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ()).with_max_actors(2);
+    ///
+    /// let first = system.add_id(Tenant).await.unwrap();
+    /// system.add_id(Tenant).await.unwrap();
+    /// assert_eq!(system.add_id(Tenant).await, Err(AddError::System(SystemError::AtCapacity { limit: 2 })));
+    ///
+    /// system.kill::<Tenant>(first).await;
+    /// assert!(system.add(Tenant).await.is_ok());
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn with_max_actors(mut self, limit: usize) -> Self {
+        self.max_actors = Some(limit);
+        self
+    }
+
+    /// # [`Fluxion::with_foreign_cache`]
+    /// Enables caching of up to `capacity` foreign actor references retrieved from the delegate,
+    /// so that repeated calls to [`Fluxion::get`] for the same foreign actor do not
+    /// call [`Delegate::get_actor`] every time. When the cache is full, the least recently
+    /// retrieved reference is evicted. A cached reference is also evicted whenever it fails to send a message.
+    ///
+    /// <div class = "warn">
+    /// The cache is not locked while the delegate resolves an actor. If several tasks retrieve the same uncached actor at once,
+    /// each of them calls the delegate, and the reference resolved last replaces the others in the cache.
+    /// </div>
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")] {
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, Identifier, IndeterminateMessage, MessageSender, ValidationError};
+    /// # use serde::{Deserialize, Serialize};
+    /// # use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+    /// # #[actor] struct Doubler;
+    /// # #[message(u64)] #[derive(Serialize, Deserialize)] struct Double(u64);
+    /// # impl Handler<Double> for Doubler {
+    /// #     async fn handle_message<D: Delegate>(&self, message: Double, _context: &ActorContext<D>) -> u64 { message.0 * 2 }
+    /// #     fn validate(message: &Double) -> Result<(), ValidationError> {
+    /// #         if message.0 == 0 { Err(ValidationError::new("nothing to double")) } else { Ok(()) }
+    /// #     }
+    /// # }
+    /// /// Resolves foreign actors on another system, counting how often it is asked to
+    /// struct CountingDelegate {
+    ///     remote: Fluxion,
+    ///     lookups: AtomicUsize,
+    /// }
+    ///
+    /// impl Delegate for CountingDelegate {
+    ///     async fn get_actor<A: Handler<M>, M: IndeterminateMessage>(&self, id: Identifier<'_>) -> Option<Arc<dyn MessageSender<M>>>
+    ///         where M::Result: Serialize + for<'a> Deserialize<'a> {
+    ///         self.lookups.fetch_add(1, Ordering::Relaxed);
+    ///
+    ///         let Identifier::Foreign(id, _) = id else { return None };
+    ///         Some(Arc::new(self.remote.get_local::<A>(id).await.ok()?))
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let remote = Fluxion::new("remote", ());
+    /// let a = remote.add_id(Doubler).await.unwrap();
+    /// let b = remote.add_id(Doubler).await.unwrap();
+    ///
+    /// let delegate = CountingDelegate { remote, lookups: AtomicUsize::new(0) };
+    /// let system = Fluxion::new("local", delegate).with_foreign_cache(1);
+    /// let lookups = || system.get_delegate().lookups.load(Ordering::Relaxed);
+    ///
+    /// // Repeated retrievals of the same actor only call the delegate once
+    /// system.get::<Doubler, Double>(Identifier::Foreign(a, "remote")).await.unwrap();
+    /// system.get::<Doubler, Double>(Identifier::Foreign(a, "remote")).await.unwrap();
+    /// assert_eq!(lookups(), 1);
+    ///
+    /// // The cache holds a single reference, so retrieving another actor evicts the first
+    /// system.get::<Doubler, Double>(Identifier::Foreign(b, "remote")).await.unwrap();
+    /// let doubler = system.get::<Doubler, Double>(Identifier::Foreign(a, "remote")).await.unwrap();
+    /// assert_eq!(lookups(), 3);
+    ///
+    /// // A failed send evicts the reference, so the next retrieval calls the delegate again
+    /// assert!(doubler.send(Double(0)).await.is_err());
+    /// system.get::<Doubler, Double>(Identifier::Foreign(a, "remote")).await.unwrap();
+    /// assert_eq!(lookups(), 4);
+    ///
+    /// // Successful sends keep the reference cached
+    /// let doubler = system.get::<Doubler, Double>(Identifier::Foreign(a, "remote")).await.unwrap();
+    /// assert_eq!(doubler.send(Double(21)).await.unwrap(), 42);
+    /// system.get::<Doubler, Double>(Identifier::Foreign(a, "remote")).await.unwrap();
+    /// assert_eq!(lookups(), 4);
+    /// # });
+    /// # }
+    /// ```
+    #[cfg(feature = "foreign")]
+    #[must_use]
+    pub fn with_foreign_cache(mut self, capacity: usize) -> Self {
+        self.foreign_cache = Some(Arc::new(SenderCache::new(capacity)));
+        self
+    }
+
     /// # [`Fluxion::get_delegate`]
     /// Gets a reference to the delegate.
     #[must_use]
@@ -58,6 +646,23 @@ impl<D: Delegate> Fluxion<D> {
         &self.system_id
     }
 
+    /// # [`Fluxion::system_id_arc`]
+    /// Gets the system's id as a reference-counted string.
+    /// This is a cheap clone of the id stored by the system, which is useful
+    /// for storing the id without reallocating it.
+    ///
+    /// ```
+    /// # use fluxion::Fluxion;
+    /// let system = Fluxion::new("system", ());
+    /// let id = system.system_id_arc();
+    ///
+    /// assert_eq!(&*id, system.get_id());
+    /// ```
+    #[must_use]
+    pub fn system_id_arc(&self) -> Arc<str> {
+        self.system_id.clone()
+    }
+
     /// # [`Fluxion::get_actor_id`]
     /// Retrieve's an actor's ID by its name
     #[must_use]
@@ -65,6 +670,120 @@ impl<D: Delegate> Fluxion<D> {
         self.actor_ids.read().await.get(name).copied()
     }
 
+    /// # [`Fluxion::actor_type_name`]
+    /// Retrieves the type name of the actor with the given id, as returned by [`core::any::type_name`].
+    /// Returns [`None`] if no actor with the given id exists.
+    /// This is intended for diagnostics, and the exact contents of the name are not guaranteed to be stable.
+    ///
+    /// ```
+    /// # use fluxion::{actor, Fluxion};
+    /// # #[actor] struct Counter;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let id = system.add_id(Counter).await.unwrap();
+    ///
+    /// assert!(system.actor_type_name(id).await.unwrap().ends_with("Counter"));
+    /// # });
+    /// ```
+    #[must_use]
+    pub async fn actor_type_name(&self, id: u64) -> Option<&'static str> {
+        self.actors.read().await.entries.get(&id).map(|entry| entry.type_name)
+    }
+
+    /// # [`Fluxion::actor_count`]
+    /// Returns the number of live actors on the local system.
+    /// Actors that have been killed, or removed by [`Fluxion::shutdown`], are not counted.
+    #[must_use]
+    pub async fn actor_count(&self) -> usize {
+        self.actors.read().await.entries.len()
+    }
+
+    /// # [`Fluxion::actor_ids`]
+    /// Returns the ids of every live actor on the local system, in ascending order.
+    ///
+    /// ```
+    /// # use fluxion::{actor, Fluxion};
+    /// # #[actor] struct Worker;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let a = system.add_id(Worker).await.unwrap();
+    /// let b = system.add_id(Worker).await.unwrap();
+    /// let c = system.add_id(Worker).await.unwrap();
+    ///
+    /// system.kill::<Worker>(b).await;
+    /// assert_eq!(system.actor_count().await, 2);
+    /// assert_eq!(system.actor_ids().await, vec![a, c]);
+    ///
+    /// system.shutdown().await;
+    /// assert_eq!(system.actor_count().await, 0);
+    /// # });
+    /// ```
+    #[must_use]
+    pub async fn actor_ids(&self) -> Vec<u64> {
+        self.actors.read().await.entries.keys().copied().collect()
+    }
+
+    /// # [`Fluxion::metrics_snapshot`]
+    /// Returns the number of messages handled and in flight, summed over every live actor on the local system and per actor type.
+    /// Messages are counted as in flight while their handler is running. The counters of killed actors are not included.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSender};
+    /// # #[actor] struct Worker;
+    /// # #[actor] struct Logger;
+    /// # #[message] struct Work;
+    /// # impl Handler<Work> for Worker {
+    /// #     async fn handle_message<D: Delegate>(&self, _message: Work, _context: &ActorContext<D>) {}
+    /// # }
+    /// # impl Handler<Work> for Logger {
+    /// #     async fn handle_message<D: Delegate>(&self, _message: Work, _context: &ActorContext<D>) {}
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let first = system.add(Worker).await.unwrap();
+    /// let second = system.add(Worker).await.unwrap();
+    /// let logger = system.add(Logger).await.unwrap();
+    ///
+    /// for _ in 0..3 {
+    ///     first.send(Work).await.unwrap();
+    /// }
+    /// second.send(Work).await.unwrap();
+    /// logger.send(Work).await.unwrap();
+    ///
+    /// let snapshot = system.metrics_snapshot().await;
+    /// assert_eq!(snapshot.actor_count, 3);
+    /// assert_eq!(snapshot.total_handled, 5);
+    /// assert_eq!(snapshot.total_in_flight, 0);
+    ///
+    /// let workers = snapshot.by_type[core::any::type_name::<Worker>()];
+    /// assert_eq!((workers.actors, workers.handled), (2, 4));
+    ///
+    /// // Killed actors no longer count towards the totals
+    /// system.kill::<Worker>(first.get_id()).await;
+    /// assert_eq!(system.metrics_snapshot().await.total_handled, 2);
+    /// # });
+    /// ```
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub async fn metrics_snapshot(&self) -> crate::SystemMetrics {
+        let actors = self.actors.read().await;
+
+        let mut snapshot = crate::SystemMetrics::default();
+        for entry in actors.entries.values() {
+            let (handled, in_flight) = entry.counters.load();
+            snapshot.actor_count += 1;
+            snapshot.total_handled += handled;
+            snapshot.total_in_flight += in_flight;
+
+            let by_type = snapshot.by_type.entry(entry.type_name).or_default();
+            by_type.actors += 1;
+            by_type.handled += handled;
+            by_type.in_flight += in_flight;
+        }
+
+        snapshot
+    }
+
     /// # [`Fluxion::add_named`]
     /// Adds an actor to the local instance, returning its id and assigning
     /// the given name to it for retrieval by [`Fluxion::get_actor_id`].
@@ -73,167 +792,1412 @@ impl<D: Delegate> Fluxion<D> {
     /// Locks the underlying RwLock as write. This will block "management" functionalities such as adding, removing, and retrieving actors, but
     /// will not block any messages.
     /// </div>
-    /// <div class = "warn">
-    ///     If an actor with a duplicate name is added, it will overwrite the original actor's name.
-    ///     The original actor won't be killed, but it may become inaccessible.
-    /// </div>
+    ///
+    /// Names are unique, and are released when their actor is killed.
     /// 
     /// # Errors
-    /// Returns an error if the actor failed to initialize.
+    /// Returns [`SystemError::NameTaken`] if another actor already has the given name, or is being added with it.
+    /// Otherwise, returns an error if the actor failed to initialize, or if the system is at its actor limit.
     /// On an error, the actor will not be spawned, and the name will not be assigned.
-    pub async fn add_named<A: Actor>(&self, name: &str, actor: A) -> Result<u64, A::Error> {
+    pub async fn add_named<A: Actor>(&self, name: &str, actor: A) -> Result<u64, AddError<A::Error>> {
+        // Claim the name, so that nobody else can add an actor with it in the meantime
+        let claim = NameClaim::new(self, name).ok_or(SystemError::NameTaken)?;
+
+        if self.get_actor_id(name).await.is_some() {
+            return Err(SystemError::NameTaken.into());
+        }
+
+        // The claim is released once the actor is added, even if this future is dropped.
+        let res = self.add_claimed(name, actor).await;
+        drop(claim);
+
+        res
+    }
+
+    /// Adds an actor with the given name, which must already be claimed by the caller
+    async fn add_claimed<A: Actor>(&self, name: &str, actor: A) -> Result<u64, AddError<A::Error>> {
         // Add the actor, assigning an id
-        let id = self.add(actor).await?;
+        let id = self.add_id(actor).await?;
 
         // Store the actor's name in the actor_ids map
         let mut actor_ids = self.actor_ids.write().await;
         actor_ids.insert(String::from(name), id as u64);
+        drop(actor_ids);
+
+        // Anyone waiting for this name can now find the actor
+        self.readiness.wake_all();
 
         // Return the actor's id.
         Ok(id)
     }
 
+    /// # [`Fluxion::get_or_spawn_named`]
+    /// Retrieves the id of the actor with the given name, spawning it if it does not exist.
+    /// To spawn the actor, `factory` is awaited to create it, and it is then added as if by [`Fluxion::add_named`].
+    ///
+    /// If several tasks call this concurrently with the same name, the factory is only run by one of them,
+    /// and the rest wait for it to finish and return the same id. No locks are held while the factory runs.
+    /// If the factory's actor fails to be added, one of the waiting tasks will try again with its own factory.
+    ///
+    /// <div class = "warn">
+    /// If an actor with the given name already exists, its id is returned even if it is not of type `A`.
+    /// </div>
+    ///
+    /// ```
+    /// # use fluxion::{actor, Fluxion};
+    /// # #[actor] struct Connection;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    ///
+    /// let (a, b) = tokio::join!(
+    ///     system.get_or_spawn_named("db", || async { Connection }),
+    ///     system.get_or_spawn_named("db", || async { Connection }),
+    /// );
+    ///
+    /// assert_eq!(a.unwrap(), b.unwrap());
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the actor created by this call failed to be added.
+    pub async fn get_or_spawn_named<A: Actor, F: Future<Output = A>>(&self, name: &str, factory: impl FnOnce() -> F) -> Result<u64, AddError<A::Error>> {
+        // Claim the name, or wait for whoever has claimed it to finish
+        let claim = loop {
+            // Start waiting before checking, so that any
+            // wakeups that occur during the check are not missed.
+            let wait = self.readiness.wait();
+
+            if let Some(id) = self.get_actor_id(name).await {
+                return Ok(id);
+            }
+
+            if let Some(claim) = NameClaim::new(self, name) {
+                break claim;
+            }
+
+            // The queue is never closed, so this can't fail
+            let _ = wait.await;
+        };
+
+        // The actor may have been spawned between checking for it and claiming the name
+        if let Some(id) = self.get_actor_id(name).await {
+            return Ok(id);
+        }
+
+        // Spawn the actor. The claim is released once this is done, even if this future is dropped.
+        let res = self.add_claimed(name, factory().await).await;
+        drop(claim);
+
+        res
+    }
+
+    /// # [`Fluxion::get_or_add_named`]
+    /// Retrieves the actor with the given name, creating and adding it if it does not exist.
+    /// This behaves like [`Fluxion::get_or_spawn_named`], but `make` is not async, and a reference
+    /// to the actor is returned instead of its id. `make` is only called if the actor needs to be added.
+    ///
+    /// ```
+    /// # use fluxion::{actor, AddError, Fluxion, SystemError};
+    /// # #[actor] struct Supervisor;
+    /// # #[actor] struct Other;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    ///
+    /// let first = system.get_or_add_named("supervisor", || Supervisor).await.unwrap();
+    /// let second = system.get_or_add_named("supervisor", || -> Supervisor { unreachable!() }).await.unwrap();
+    /// assert_eq!(first.get_id(), second.get_id());
+    ///
+    /// // The existing actor must be of the requested type
+    /// let res = system.get_or_add_named("supervisor", || Other).await;
+    /// assert!(matches!(res, Err(AddError::System(SystemError::TypeMismatch { .. }))));
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the actor created by this call failed to be added.
+    /// Returns [`SystemError::TypeMismatch`] if an actor with the given name exists, but is not of type `A`,
+    /// and [`SystemError::NotFound`] if the actor was killed before a reference to it could be retrieved.
+    pub async fn get_or_add_named<A: Actor>(&self, name: &str, make: impl FnOnce() -> A) -> Result<LocalRef<A, D>, AddError<A::Error>> {
+        let id = self.get_or_spawn_named(name, || core::future::ready(make())).await?;
+
+        Ok(self.get_local(id).await?)
+    }
+
+    /// # [`Fluxion::swap_names`]
+    /// Atomically exchanges the names of two actors, so that lookups of either actor's name resolve to the other actor.
+    /// This allows a named actor to be replaced without downtime: add the replacement, swap their names,
+    /// and kill the original once it has finished handling the messages it already received.
+    /// References that were retrieved before the swap still refer to the same actor as before.
+    ///
+    /// If only one of the actors has a name, the name is moved to the other actor.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler};
+    /// # #[actor] struct Service(&'static str);
+    /// # #[message(&'static str)] struct Version;
+    /// # impl Handler<Version> for Service {
+    /// #     async fn handle_message<D: Delegate>(&self, _message: Version, _context: &ActorContext<D>) -> &'static str { self.0 }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let blue = system.add_named("service", Service("blue")).await.unwrap();
+    /// let green = system.add_id(Service("green")).await.unwrap();
+    ///
+    /// system.swap_names(blue, green).await.unwrap();
+    ///
+    /// let service = system.get_named::<Service, Version>("service").await.unwrap();
+    /// assert_eq!(service.send(Version).await.unwrap(), "green");
+    ///
+    /// system.kill::<Service>(blue).await;
+    /// assert_eq!(system.get_actor_id("service").await, Some(green));
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`SystemError::NotFound`] if either actor does not exist. In that case, no names are changed.
+    pub async fn swap_names(&self, a: u64, b: u64) -> Result<(), SystemError> {
+        let mut actor_ids = self.actor_ids.write().await;
+
+        // Make sure that both actors exist while the names are locked
+        let actors = self.actors.read().await;
+        if !actors.entries.contains_key(&a) || !actors.entries.contains_key(&b) {
+            return Err(SystemError::NotFound);
+        }
+        drop(actors);
+
+        for id in actor_ids.values_mut() {
+            if *id == a {
+                *id = b;
+            } else if *id == b {
+                *id = a;
+            }
+        }
+
+        // Wake anyone waiting for a name to be assigned
+        self.readiness.wake_all();
+
+        Ok(())
+    }
+
     /// # [`Fluxion::add`]
-    /// Adds an actor to the local instance, returning its id.
+    /// Adds an actor to the local instance, returning a reference to it.
+    /// Use [`LocalRef::get_id`] to retrieve its id, or [`Fluxion::add_id`] if only the id is needed.
     /// <div class = "info">
     /// Locks the underlying RwLock as write. This will block "management" functionalities such as adding, removing, and retrieving actors, but
     /// will not block any messages.
     /// </div>
     /// 
     /// # Errors
-    /// Returns an error if the actor failed to initialize.
+    /// Returns an error if the actor failed to initialize, if the system is at its actor limit, or if it has been shut down.
+    /// On an error, the actor will not be spawned.
+    pub async fn add<A: Actor>(&self, actor: A) -> Result<LocalRef<A, D>, AddError<A::Error>> {
+        self.add_with_parent(actor, None, None).await
+    }
+
+    /// # [`Fluxion::add_id`]
+    /// Adds an actor to the local instance, returning its id.
+    /// This is the same as [`Fluxion::add`], but does not return a reference to the actor.
+    ///
+    /// With the `deterministic-ids` feature, ids are assigned from a counter and never reused,
+    /// so the same sequence of adds and kills always assigns the same ids.
+    /// On a system made with [`Fluxion::new_sharded`], each id is the counter's value times the number of shards, plus the actor's shard.
+    ///
+    /// ```
+    /// # use fluxion::{actor, Fluxion};
+    /// # #[actor] struct Worker;
+    /// # #[cfg(feature = "deterministic-ids")]
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    ///
+    /// let first = system.add_id(Worker).await.unwrap();
+    /// let second = system.add_id(Worker).await.unwrap();
+    /// system.kill::<Worker>(first).await;
+    /// let third = system.add_id(Worker).await.unwrap();
+    ///
+    /// // The first actor's id is not reused
+    /// assert_eq!([first, second, third], [0, 1, 2]);
+    ///
+    /// // Actors are added to the two shards in turn
+    /// let sharded = Fluxion::new_sharded("sharded", (), 2);
+    ///
+    /// let first = sharded.add_id(Worker).await.unwrap();
+    /// let second = sharded.add_id(Worker).await.unwrap();
+    /// sharded.kill::<Worker>(first).await;
+    /// let third = sharded.add_id(Worker).await.unwrap();
+    ///
+    /// assert_eq!([first, second, third], [0, 3, 4]);
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the actor failed to initialize, or if the system is at its actor limit.
     /// On an error, the actor will not be spawned.
-    pub async fn add<A: Actor>(&self, mut actor: A) -> Result<u64, A::Error> {
+    pub async fn add_id<A: Actor>(&self, actor: A) -> Result<u64, AddError<A::Error>> {
+        self.add(actor).await.map(|actor| actor.get_id())
+    }
+
+    /// # [`Fluxion::add_deduplicated`]
+    /// Adds an actor to the local instance, unless an equivalent actor already exists, returning a reference to whichever actor is on the system.
+    /// Two actors are equivalent if they are of the same type, and `identity` returned the same value for both when they were added.
+    /// This prevents a singleton from accidentally being added twice, without having to give it a name.
+    /// Only actors added with this function are considered, and an actor's identity is released when it is killed.
+    ///
+    /// If an equivalent actor exists, `actor` is dropped without being initialized.
+    /// If an equivalent actor is added while `actor` is being initialized, `actor` is deinitialized and discarded.
+    ///
+    /// ```
+    /// # use fluxion::{actor, Fluxion};
+    /// # #[actor] struct Connection { url: &'static str }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    ///
+    /// let a = system.add_deduplicated(Connection { url: "db" }, |c| c.url.into()).await.unwrap();
+    /// let b = system.add_deduplicated(Connection { url: "db" }, |c| c.url.into()).await.unwrap();
+    /// assert_eq!(a.get_id(), b.get_id());
+    /// assert_eq!(system.actor_count().await, 1);
+    ///
+    /// let c = system.add_deduplicated(Connection { url: "cache" }, |c| c.url.into()).await.unwrap();
+    /// assert_ne!(a.get_id(), c.get_id());
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the actor failed to initialize, or if the system is at its actor limit.
+    /// On an error, the actor will not be spawned.
+    pub async fn add_deduplicated<A: Actor>(&self, actor: A, identity: impl FnOnce(&A) -> String) -> Result<LocalRef<A, D>, AddError<A::Error>> {
+        let identity = identity(&actor);
+
+        // Check for an equivalent actor before initializing this one.
+        // This is checked again once the actor is initialized.
+        {
+            let system = self.shards[self.identity_shard(&identity)].read().await;
+            let actors = self.actors.read().await;
+
+            if let Some(existing) = self.find_identity(&system, &actors, &identity) {
+                return Ok(existing);
+            }
+        }
+
+        self.add_with_parent(actor, None, Some(identity)).await
+    }
+
+    /// Retrieves the actor of type `A` that was added with the given identity, if any.
+    /// `system` must be the identity's shard.
+    fn find_identity<A: Actor>(&self, system: &Slacktor, actors: &ActorTable, identity: &str) -> Option<LocalRef<A, D>> {
+        actors.entries.iter()
+            .filter(|(_, entry)| entry.identity.as_deref() == Some(identity))
+            .find_map(|(id, entry)| {
+                // Actors of other types may share the identity
+                let handle = system.get::<ActorWrapper<A, D>>(entry.key)?;
+                Some(LocalRef(handle.clone(), *id, entry.inflight.clone(), self.metrics.clone(), self.timer.clone()))
+            })
+    }
+
+    /// Adds an actor to the local instance with the given parent and identity, returning a reference to it.
+    /// If an actor of the same type with the same identity already exists, that actor is returned instead.
+    pub(crate) async fn add_with_parent<A: Actor>(&self, mut actor: A, parent: Option<u64>, identity: Option<String>) -> Result<LocalRef<A, D>, AddError<A::Error>> {
+
+        self.ensure_running()?;
+
+        // Don't bother initializing the actor if the system is already full
+        if let Some(limit) = self.max_actors {
+            if self.actors.read().await.entries.len() >= limit {
+                return Err(AddError::System(SystemError::AtCapacity { limit }));
+            }
+        }
 
         // Run the actor's initialization code
-        actor.initialize().await?;
+        actor.initialize().await.map_err(AddError::Initialize)?;
 
-        // Lock the underlying slacktor instance as write
-        let mut system = self.slacktor.write().await;
+        // Choose the actor's shard. Children are stored with their parents, so that killing an actor only locks one shard,
+        // and equivalent actors are stored together, so that they can be found while holding one shard.
+        let shard = match (parent, identity.as_deref()) {
+            (Some(parent), _) => self.shard_index(parent),
+            (None, Some(identity)) => self.identity_shard(identity),
+            (None, None) => self.next_shard.fetch_add(1, Ordering::Relaxed) % self.shards.len(),
+        };
+
+        // Lock the actor's shard and the actor table as write
+        let mut system = self.shards[shard].write().await;
+        let mut actors = self.actors.write().await;
+
+        // Check the limit again, as other actors may have been added during initialization.
+        // If the system has filled up, or has been shut down in the meantime,
+        // the initialized actor is deinitialized and discarded.
+        let full = self.max_actors.filter(|limit| actors.entries.len() >= *limit);
+        if let Err(e) = self.ensure_running() {
+            drop(actors);
+            drop(system);
+            actor.deinitialize().await;
+            return Err(AddError::System(e));
+        }
+        if let Some(limit) = full {
+            drop(actors);
+            drop(system);
+            actor.deinitialize().await;
+            return Err(AddError::System(SystemError::AtCapacity { limit }));
+        }
+
+        // The parent may have been killed during initialization. Its children are killed along with it,
+        // so the child is discarded rather than being left behind with a stale parent id.
+        if parent.is_some_and(|parent| !actors.entries.contains_key(&parent)) {
+            drop(actors);
+            drop(system);
+            actor.deinitialize().await;
+            return Err(AddError::System(SystemError::NotFound));
+        }
+
+        // An equivalent actor may have been added during initialization
+        if let Some(existing) = identity.as_deref().and_then(|identity| self.find_identity(&system, &actors, identity)) {
+            drop(actors);
+            drop(system);
+            actor.deinitialize().await;
+            return Ok(existing);
+        }
+
+        // Assign the actor's id, which is encoded so that its remainder is the actor's shard.
+        // Unless deterministic ids are enabled, it is derived from the key of the slot the actor will occupy.
+        #[cfg(feature = "deterministic-ids")]
+        let index = actors.next_id;
+        #[cfg(not(feature = "deterministic-ids"))]
+        let index = system.next_id();
+        let id = index * self.shards.len() as u64 + shard as u64;
 
         // Wrap the actor
+        #[cfg(feature = "metrics")]
+        let counters = Arc::new(crate::metrics::ActorCounters::default());
         let actor = ActorWrapper(actor, Arc::new(
             ActorContext {
                 system: self.clone(),
-                id: system.next_id()
+                id,
+                parent,
+                #[cfg(feature = "metrics")]
+                counters: counters.clone(),
             }
         ));
 
-        // Spawn the actor on the slacktor instance
-        let id = system.spawn(actor);
+        // Spawn the actor on its shard, and take a handle to it while the lock is still held
+        let key = system.spawn(actor);
+        let handle = system.get::<ActorWrapper<A, D>>(key)
+            .cloned()
+            .expect("the actor was just spawned with this type");
 
+        // Record the actor's entry
+        let inflight = A::MAILBOX_CAPACITY.map(|capacity| Arc::new(Semaphore::new(capacity)));
+        actors.entries.insert(id, ActorEntry {
+            key,
+            type_name: core::any::type_name::<A>(),
+            type_id: TypeId::of::<A>(),
+            ready: !A::MANUAL_READINESS,
+            kill: kill_actor::<A, D>,
+            deinit: deinit_actor::<A, D>,
+            inflight: inflight.clone(),
+            shutdown_priority: A::SHUTDOWN_PRIORITY,
+            identity,
+            parent,
+            #[cfg(feature = "metrics")]
+            counters,
+        });
 
-        // Return the actor's id.
-        Ok(id as u64)
+        // Advance the id counter
+        #[cfg(feature = "deterministic-ids")]
+        {
+            actors.next_id += 1;
+        }
+
+        // Wake anyone waiting for the actor to become ready
+        if !A::MANUAL_READINESS {
+            self.readiness.wake_all();
+        }
+
+        // Return a reference to the actor
+        Ok(LocalRef(handle, id, inflight, self.metrics.clone(), self.timer.clone()))
+    }
+
+    /// Marks the actor with the given id as ready
+    pub(crate) async fn mark_ready(&self, id: u64) {
+        if let Some(entry) = self.actors.write().await.entries.get_mut(&id) {
+            entry.ready = true;
+        }
+
+        self.readiness.wake_all();
+    }
+
+    /// # [`Fluxion::await_ready`]
+    /// Waits until every actor with one of the given names has been added and marked as ready.
+    /// Actors are ready once initialized, unless they set [`Actor::MANUAL_READINESS`],
+    /// in which case they are ready once they call [`ActorContext::mark_ready`].
+    ///
+    /// <div class = "warn">
+    /// If one of the named actors is never added, or never marks itself as ready, this will wait forever.
+    /// </div>
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, Actor, ActorContext, Delegate, Fluxion, Handler, MessageSender};
+    /// # use std::time::Duration;
+    /// /// Becomes ready once it has connected, after being added
+    /// struct Database;
+    ///
+    /// impl Actor for Database {
+    ///     type Error = ();
+    ///     const MANUAL_READINESS: bool = true;
+    /// }
+    ///
+    /// #[message] struct Connect;
+    ///
+    /// impl Handler<Connect> for Database {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Connect, context: &ActorContext<D>) {
+    ///         context.mark_ready().await;
+    ///     }
+    /// }
+    ///
+    /// /// Ready as soon as it is initialized
+    /// #[actor] struct Cache;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let waiter = tokio::spawn({
+    ///     let system = system.clone();
+    ///     async move { system.await_ready(&["database", "cache"]).await }
+    /// });
+    ///
+    /// let database = system.add_named("database", Database).await.unwrap();
+    /// system.add_named("cache", Cache).await.unwrap();
+    ///
+    /// // The cache is ready, but the database is not
+    /// tokio::time::sleep(Duration::from_millis(20)).await;
+    /// assert!(!waiter.is_finished());
+    ///
+    /// system.get_local::<Database>(database).await.unwrap().send(Connect).await.unwrap();
+    /// waiter.await.unwrap();
+    /// # });
+    /// ```
+    pub async fn await_ready(&self, names: &[&str]) {
+        loop {
+            // Start waiting before checking, so that any
+            // wakeups that occur during the check are not missed.
+            let wait = self.readiness.wait();
+
+            if self.all_ready(names).await {
+                return;
+            }
+
+            // The queue is never closed, so this can't fail
+            let _ = wait.await;
+        }
+    }
+
+    /// Returns true if every actor with one of the given names exists and is ready
+    async fn all_ready(&self, names: &[&str]) -> bool {
+        let actor_ids = self.actor_ids.read().await;
+        let actors = self.actors.read().await;
+
+        names.iter().all(|name| {
+            actor_ids.get(*name)
+                .and_then(|id| actors.entries.get(id))
+                .is_some_and(|entry| entry.ready)
+        })
     }
 
     /// # [`Fluxion::kill`]
-    /// Given an actor's id, kills the actor
+    /// Given an actor's id, kills the actor.
+    /// Returns true if an actor of type `A` with the given id existed, and false if there was nothing to kill.
+    /// If the actor with the given id is of a different type, it is left untouched and false is returned.
+    /// Any children the actor added with [`ActorContext::add_child`] are killed first, recursively.
+    ///
+    /// ```
+    /// # use fluxion::{actor, Fluxion};
+    /// # #[actor] struct Worker;
+    /// # #[actor] struct Other;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let id = system.add_id(Worker).await.unwrap();
+    ///
+    /// // The actor is not an `Other`, so it is not killed
+    /// assert!(!system.kill::<Other>(id).await);
+    /// assert!(system.get_local::<Worker>(id).await.is_ok());
+    ///
+    /// assert!(system.kill::<Worker>(id).await);
+    /// assert!(!system.kill::<Worker>(id).await);
+    /// # });
+    /// ```
     /// 
     /// <div class = "info">
     /// Locks the underlying RwLock as write. This will block "management" functionalities such as adding, removing, and retrieving actors, but
     /// will not block any messages.
     /// </div>
-    pub async fn kill<A: Actor>(&self, id: u64) {
-        // Realistically, it should not be possible for this conversion to ever fail.
-        // If the input id is more than usize::MAX, it is most likely an error on the caller's part,
-        // as it should be impossible to allocate over usize::MAX actors at all, because
-        // each actor has an overhead of more than one byte.
-        // We just fail silently here, as it is the same case as the actor not existing.
-        let Ok(id) = id.try_into() else {
-            return;
+    pub async fn kill<A: Actor>(&self, id: u64) -> bool {
+        self.kill_any(id, TypeId::of::<A>()).await
+    }
+
+    /// Kills the actor with the given id, without knowing its type.
+    /// Only kills the actor if its type id matches `type_id`.
+    /// Returns false if no such actor exists.
+    pub(crate) async fn kill_any(&self, id: u64, type_id: TypeId) -> bool {
+        // Lock the actor's shard as write. Its descendants are stored on the same shard.
+        let mut system = self.shard(id).write().await;
+
+        // Remove the actor's entry, as long as it is still the same actor
+        let mut actors = self.actors.write().await;
+        if actors.entries.get(&id).map(|entry| entry.type_id) != Some(type_id) {
+            return false;
+        }
+        let Some(entry) = actors.entries.remove(&id) else {
+            return false;
         };
+        let descendants = actors.take_descendants(&[id]);
+        drop(actors);
+
+        // Release the actors' names
+        let mut ids = descendants.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        ids.push(id);
+        self.release_names(&ids).await;
+
+        // Kill the descendants, and then the actor itself, using the functions recorded when they were added
+        for (_, descendant) in descendants {
+            (descendant.kill)(&mut system, descendant.key).await;
+        }
+        (entry.kill)(&mut system, entry.key).await;
+
+        // Shrink the shard
+        system.shrink();
+
+        // Wake anyone waiting for actors to stop
+        self.stopped.wake_all();
+
+        true
+    }
+
+    /// # [`Fluxion::kill_where`]
+    /// Kills every actor for which `predicate` returns true, returning the ids of the killed actors.
+    /// Killed actors are deinitialized as usual. Children of killed actors are killed too, as if by [`Fluxion::kill`],
+    /// and are included in the returned ids.
+    ///
+    /// <div class = "info">
+    /// Locks every shard as write for the duration of the call. This will block "management" functionalities
+    /// such as adding, removing, and retrieving actors, but will not block any messages.
+    /// </div>
+    ///
+    /// ```
+    /// # use fluxion::{actor, Fluxion};
+    /// # #[actor] struct Session;
+    /// # #[actor] struct Database;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let idle = system.add_named("session/idle", Session).await.unwrap();
+    /// let active = system.add_named("session/active", Session).await.unwrap();
+    /// let database = system.add_id(Database).await.unwrap();
+    ///
+    /// let killed = system.kill_where(|info| info.name.is_some_and(|name| name.ends_with("/idle"))).await;
+    ///
+    /// assert_eq!(killed, vec![idle]);
+    /// assert!(system.get_local::<Session>(active).await.is_ok());
+    /// assert!(system.get_local::<Database>(database).await.is_ok());
+    /// # });
+    /// ```
+    pub async fn kill_where(&self, predicate: impl Fn(&ActorInfo) -> bool) -> Vec<u64> {
+        // Lock every shard as write
+        let mut shards = self.write_shards().await;
+
+        // Find the actors to kill, removing their entries
+        let mut killed = Vec::new();
+        {
+            let actor_ids = self.actor_ids.read().await;
+            let mut actors = self.actors.write().await;
+
+            actors.entries.retain(|id, entry| {
+                let info = ActorInfo {
+                    id: *id,
+                    type_name: entry.type_name,
+                    name: actor_ids.iter()
+                        .find(|(_, named)| *named == id)
+                        .map(|(name, _)| name.as_str()),
+                };
 
-        // Lock the underylying slacktor instance as write and kill the actor
-        self.slacktor.write().await.kill::<ActorWrapper<A, D>>(id).await;
+                if predicate(&info) {
+                    killed.push((*id, entry.key, entry.kill));
+                    false
+                } else {
+                    true
+                }
+            });
 
-        // Shrink the slacktor instance
-        self.slacktor.write().await.shrink();
+            // Kill the descendants of the matching actors first
+            let ids = killed.iter().map(|(id, _, _)| *id).collect::<Vec<_>>();
+            let descendants = actors.take_descendants(&ids);
+            killed.splice(0..0, descendants.into_iter().map(|(id, entry)| (id, entry.key, entry.kill)));
+        }
+
+        // Release the actors' names
+        let ids = killed.iter().map(|(id, _, _)| *id).collect::<Vec<_>>();
+        self.release_names(&ids).await;
+
+        // Kill the actors using the functions recorded when they were added
+        for (id, key, kill) in &killed {
+            kill(&mut shards[self.shard_index(*id)], *key).await;
+        }
+
+        // Shrink the shards
+        for shard in &mut shards {
+            shard.shrink();
+        }
+        drop(shards);
+
+        // Wake anyone waiting for actors to stop
+        if !killed.is_empty() {
+            self.stopped.wake_all();
+        }
+
+        ids
     }
 
+    /// Removes the names of the actors with the given ids, so that they can be reused
+    async fn release_names(&self, ids: &[u64]) {
+        self.actor_ids.write().await.retain(|_, id| !ids.contains(id));
+    }
+
+    /// # [`Fluxion::get_local_any`]
+    /// Gets a type-erased reference to a local actor, which can be used to manage the actor without knowing its type.
+    /// The returned [`AnyLocalRef`] can not send messages.
+    /// Returns [`None`] if no actor with the given id exists.
+    ///
+    /// ```
+    /// # use fluxion::{actor, Fluxion};
+    /// # #[actor] struct Worker;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let id = system.add_id(Worker).await.unwrap();
+    ///
+    /// let actor = system.get_local_any(id).await.unwrap();
+    /// assert!(actor.is_alive().await);
+    ///
+    /// actor.kill().await;
+    /// assert!(!actor.is_alive().await);
+    /// assert!(system.get_local_any(id).await.is_none());
+    /// # });
+    /// ```
+    #[must_use]
+    pub async fn get_local_any(&self, id: u64) -> Option<AnyLocalRef<D>> {
+        let (type_name, type_id) = self.actors.read().await.entries.get(&id)
+            .map(|entry| (entry.type_name, entry.type_id))?;
+
+        Some(AnyLocalRef {
+            system: self.clone(),
+            id,
+            type_name,
+            type_id,
+        })
+    }
+
+    /// Retrieves the type id of the actor with the given id, or [`None`] if no actor with the given id exists
+    pub(crate) async fn actor_type_id(&self, id: u64) -> Option<TypeId> {
+        self.actors.read().await.entries.get(&id).map(|entry| entry.type_id)
+    }
+
+    /// # [`Fluxion::pipe`]
+    /// Connects two local actors in a pipeline, where the response of actor `a_id` to a message `M`
+    /// is transformed into a message `N` for actor `b_id`. See [`Pipe`] for details.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler};
+    /// # #[actor] struct Doubler;
+    /// # #[actor] struct Formatter;
+    /// # #[message(u64)] struct Double(u64);
+    /// # #[message(String)] struct Format(u64);
+    /// # impl Handler<Double> for Doubler {
+    /// #     async fn handle_message<D: Delegate>(&self, message: Double, _context: &ActorContext<D>) -> u64 { message.0 * 2 }
+    /// # }
+    /// # impl Handler<Format> for Formatter {
+    /// #     async fn handle_message<D: Delegate>(&self, message: Format, _context: &ActorContext<D>) -> String { message.0.to_string() }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let doubler = system.add_id(Doubler).await.unwrap();
+    /// let formatter = system.add_id(Formatter).await.unwrap();
+    ///
+    /// let pipe = system.pipe::<Doubler, Formatter, Double, Format>(doubler, formatter, Format).await.unwrap();
+    /// assert_eq!(pipe.send(Double(21)).await.unwrap(), "42");
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if either actor can not be retrieved with [`Fluxion::get_local`].
+    pub async fn pipe<A: Handler<M>, B: Handler<N>, M: Message, N: Message>(&self, a_id: u64, b_id: u64,
+        transform: fn(M::Result) -> N) -> Result<Pipe<A, B, M, N, D>, SystemError> {
+        Ok(Pipe {
+            first: self.get_local(a_id).await?,
+            second: self.get_local(b_id).await?,
+            transform,
+        })
+    }
+
+    /// # [`Fluxion::tell_all`]
+    /// Sends a copy of `message` to each of the local actors with the given ids, without waiting for any of them to handle it.
+    /// Each send runs in a task started by the system's [`Spawner`], and its response is discarded.
+    ///
+    /// Actors that the message can't be sent to are returned along with the reason, without affecting delivery to the rest.
+    /// Ids that don't refer to a live actor of type `A` are reported with [`MessageSendError::ActorNotFound`](crate::MessageSendError::ActorNotFound),
+    /// and if [`Handler::validate`] rejects the message, every actor is reported with [`MessageSendError::Validation`](crate::MessageSendError::Validation).
+    /// Actors with a [`Actor::MAILBOX_CAPACITY`] are waited for in the background, as with [`LocalRef`]'s [`MessageSender::send`].
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSendError, Spawner};
+    /// # use std::{future::Future, pin::Pin, sync::atomic::{AtomicU64, Ordering}, time::Duration};
+    /// # struct TokioSpawner;
+    /// # impl Spawner for TokioSpawner {
+    /// #     fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>) { tokio::spawn(future); }
+    /// # }
+    /// static RELOADED: AtomicU64 = AtomicU64::new(0);
+    ///
+    /// #[actor] struct Worker;
+    /// #[message] #[derive(Clone)] struct Reload;
+    ///
+    /// impl Handler<Reload> for Worker {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Reload, _context: &ActorContext<D>) {
+    ///         RELOADED.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ()).with_spawner(TokioSpawner);
+    /// let ids = [
+    ///     system.add_id(Worker).await.unwrap(),
+    ///     system.add_id(Worker).await.unwrap(),
+    ///     system.add_id(Worker).await.unwrap(),
+    /// ];
+    /// system.kill::<Worker>(ids[1]).await;
+    ///
+    /// let failed = system.tell_all::<Worker, _>(&ids, Reload).await.unwrap();
+    /// assert!(matches!(failed[..], [(id, MessageSendError::ActorNotFound)] if id == ids[1]));
+    ///
+    /// tokio::time::sleep(Duration::from_millis(50)).await;
+    /// assert_eq!(RELOADED.load(Ordering::Relaxed), 2);
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`SystemError::NoScheduler`] if the system has no spawner,
+    /// or [`SystemError::ShuttingDown`] if the system has been shut down.
+    pub async fn tell_all<A: Handler<M>, M: Message + Clone>(&self, ids: &[u64], message: M) -> Result<Vec<(u64, crate::MessageSendError)>, SystemError> {
+        self.ensure_running()?;
+        let spawner = self.spawner().ok_or(SystemError::NoScheduler)?;
+
+        // Every actor is of the same type, so the message only needs to be validated once
+        let rejected = A::validate(&message).err();
+
+        let mut failed = Vec::new();
+        for &id in ids {
+            let Ok(actor) = self.get_local::<A>(id).await else {
+                failed.push((id, crate::MessageSendError::ActorNotFound));
+                continue;
+            };
+
+            if let Some(e) = &rejected {
+                failed.push((id, actor.send_error::<M>(crate::MessageSendError::Validation(e.clone()))));
+                continue;
+            }
+
+            let message = message.clone();
+            spawner.spawn(Box::pin(async move {
+                let _ = actor.send(message).await;
+            }));
+        }
+
+        Ok(failed)
+    }
 
     /// # [`Fluxion::get_local`]
     /// Gets an actor that is known to reside on the local system.
     /// This allows messages that are not serializable to still be used even if Fluxion is compiled with foreign message support.
     /// This function also allows retrieving an actor handle that is capable of sending multiple different messages.
-    pub async fn get_local<A: Actor>(&self, id: u64) -> Option<LocalRef<A, D>> {
-        // If the id refers to a local actor, lock the slacktor
-        // instance as read, and retrieve the handle.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, Identifier, SystemError};
+    /// # #[actor] struct Worker;
+    /// # #[actor] struct Manager;
+    /// # #[message]
+    /// # #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    /// # struct Report;
+    /// # impl Handler<Report> for Manager {
+    /// #     async fn handle_message<D: Delegate>(&self, _message: Report, _context: &ActorContext<D>) {}
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let worker = system.add_id(Worker).await.unwrap();
+    ///
+    /// // The id exists, but refers to a `Worker` rather than a `Manager`
+    /// let Err(SystemError::TypeMismatch { expected, found }) = system.get_local::<Manager>(worker).await else {
+    ///     panic!("expected a type mismatch");
+    /// };
+    /// assert!(expected.ends_with("Manager"));
+    /// assert!(found.ends_with("Worker"));
+    ///
+    /// let res = system.get::<Manager, Report>(Identifier::Local(worker)).await;
+    /// assert!(matches!(res, Err(SystemError::TypeMismatch { .. })));
+    ///
+    /// // An id that doesn't exist is reported differently
+    /// assert!(matches!(system.get_local::<Worker>(worker + 1).await, Err(SystemError::NotFound)));
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`SystemError::NotFound`] if no actor with the given id exists, and [`SystemError::TypeMismatch`]
+    /// if the actor with the given id is not of type `A`. Returns [`SystemError::ShuttingDown`] if the system has been shut down.
+    pub async fn get_local<A: Actor>(&self, id: u64) -> Result<LocalRef<A, D>, SystemError> {
+        // Lock the actor's shard and the actor table as read
+        let system = self.shard(id).read().await;
+        let actors = self.actors.read().await;
+
+        self.ensure_running()?;
+
+        // Find the actor's entry
+        let entry = actors.entries.get(&id).ok_or(SystemError::NotFound)?;
+
+        // Retrieve the handle, which will only fail if the actor is of a different type.
         // The handle is then cloned and returned
-        self.slacktor.read().await.get::<ActorWrapper<A, D>>(
-            id.try_into().ok()? // If overflow, then the actor does not exist.
-        ).cloned()
-        .map(|handle| LocalRef(handle, id))
+        system.get::<ActorWrapper<A, D>>(entry.key)
+            .cloned()
+            .map(|handle| LocalRef(handle, id, entry.inflight.clone(), self.metrics.clone(), self.timer.clone()))
+            .ok_or(SystemError::TypeMismatch {
+                expected: core::any::type_name::<A>(),
+                found: entry.type_name,
+            })
+    }
+
+    /// # [`Fluxion::get_local_named`]
+    /// Gets a local actor by the name it was added with, as if by [`Fluxion::get_local`].
+    ///
+    /// # Errors
+    /// Returns [`SystemError::NotFound`] if no actor with the given name exists, and [`SystemError::TypeMismatch`]
+    /// if the actor with the given name is not of type `A`.
+    pub async fn get_local_named<A: Actor>(&self, name: &str) -> Result<LocalRef<A, D>, SystemError> {
+        self.ensure_running()?;
+
+        let id = self.get_actor_id(name).await.ok_or(SystemError::NotFound)?;
+
+        self.get_local(id).await
+    }
+
+    /// # [`Fluxion::get_named`]
+    /// Gets a reference to a local actor by the name it was added with, which can send messages of type `M`.
+    /// Unlike [`Fluxion::get`], this never consults the delegate, so `M` does not need to support foreign messaging.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, AddError, Delegate, Fluxion, Handler, SystemError};
+    /// # #[actor] struct DbWriter;
+    /// # #[message(bool)] struct Write(&'static str);
+    /// # impl Handler<Write> for DbWriter {
+    /// #     async fn handle_message<D: Delegate>(&self, _message: Write, _context: &ActorContext<D>) -> bool { true }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// system.add_named("db_writer", DbWriter).await.unwrap();
+    ///
+    /// let writer = system.get_named::<DbWriter, Write>("db_writer").await.unwrap();
+    /// assert!(writer.send(Write("hello")).await.unwrap());
+    ///
+    /// // Names are unique
+    /// assert_eq!(system.add_named("db_writer", DbWriter).await, Err(AddError::System(SystemError::NameTaken)));
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`SystemError::NotFound`] if no actor with the given name exists, and [`SystemError::TypeMismatch`]
+    /// if the actor with the given name is not of type `A`.
+    pub async fn get_named<A: Handler<M>, M: Message>(&self, name: &str) -> Result<Arc<dyn MessageSender<M>>, SystemError> {
+        self.get_local_sender::<A, M>(&Identifier::LocalNamed(name)).await
+    }
+
+    /// # [`Fluxion::try_get_sender`]
+    /// Retrieves a reference to a local actor which can send messages of type `M`, without awaiting.
+    /// This is intended for synchronous code, which can then drive the send with whatever blocking
+    /// mechanism its executor provides.
+    ///
+    /// <div class = "warn">
+    /// Returns [`None`] if the actor does not exist or is not of type `A`, but also if the system is busy,
+    /// such as while an actor is being added or killed. A [`None`] does not mean that the actor does not exist,
+    /// and callers that can await should use [`Fluxion::get_local`] instead.
+    /// </div>
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler};
+    /// # #[actor] struct Counter;
+    /// # #[message(u64)] struct Double(u64);
+    /// # impl Handler<Double> for Counter {
+    /// #     async fn handle_message<D: Delegate>(&self, message: Double, _context: &ActorContext<D>) -> u64 { message.0 * 2 }
+    /// # }
+    /// let runtime = tokio::runtime::Runtime::new().unwrap();
+    /// let system = Fluxion::new("system", ());
+    /// let id = runtime.block_on(system.add_id(Counter)).unwrap();
+    ///
+    /// // From a plain thread, with no async context
+    /// let handle = runtime.handle().clone();
+    /// std::thread::spawn(move || {
+    ///     let sender = system.try_get_sender::<Counter, Double>(id).unwrap();
+    ///     assert_eq!(handle.block_on(sender.send(Double(21))).unwrap(), 42);
+    /// }).join().unwrap();
+    /// ```
+    #[must_use]
+    pub fn try_get_sender<A: Handler<M>, M: Message>(&self, id: u64) -> Option<Arc<dyn MessageSender<M>>> {
+        // Try to lock the actor's shard and the actor table as read, in the usual order
+        let system = self.shard(id).try_read()?;
+        let actors = self.actors.try_read()?;
+
+        if self.is_shutdown() {
+            return None;
+        }
+
+        let entry = actors.entries.get(&id)?;
+
+        system.get::<ActorWrapper<A, D>>(entry.key)
+            .cloned()
+            .map(|handle| Arc::new(LocalRef(handle, id, entry.inflight.clone(), self.metrics.clone(), self.timer.clone())) as Arc<dyn MessageSender<M>>)
+    }
+
+    /// Registers a sender that stands in for the local actor with the given id when it is retrieved for messages of type `M`
+    #[cfg(feature = "test-util")]
+    pub(crate) fn insert_stub<M: Message>(&self, id: u64, stub: Arc<dyn MessageSender<M>>) {
+        self.stubs.lock().insert((id, TypeId::of::<M>()), Arc::new(stub));
+    }
+
+    /// Retrieves the sender registered to stand in for the local actor with the given id, if any
+    #[cfg(feature = "test-util")]
+    fn stub<M: Message>(&self, id: u64) -> Option<Arc<dyn MessageSender<M>>> {
+        self.stubs.lock().get(&(id, TypeId::of::<M>()))?
+            .downcast_ref::<Arc<dyn MessageSender<M>>>()
+            .cloned()
+    }
+
+    /// Retrieves an actor reference to a local actor, given an identifier
+    /// that has already been determined to be local.
+    async fn get_local_sender<A: Handler<M>, M: Message>(&self, id: &Identifier<'_>) -> Result<Arc<dyn MessageSender<M>>, SystemError> {
+        self.ensure_running()?;
+
+        let id = match id {
+            Identifier::Local(id) => *id,
+            Identifier::LocalNamed(name) => {
+                // Get the actor's id by name
+                self.get_actor_id(name).await.ok_or(SystemError::NotFound)?
+            },
+            #[cfg(feature = "foreign")]
+            _ => return Err(SystemError::NotFound),
+        };
+
+        // Stubs registered by a test harness stand in for local actors
+        #[cfg(feature = "test-util")]
+        if let Some(stub) = self.stub::<M>(id) {
+            return Ok(stub);
+        }
+
+        // Get the local ref and wrap in an arc
+        self.get_local::<A>(id).await
+            .map(|h| Arc::new(h) as Arc<dyn MessageSender<M>>)
+    }
+
+    /// Wraps a sender returned by the delegate so that it carries the system's timer, if any
+    #[cfg(feature = "foreign")]
+    fn with_timer_of<M: Message>(&self, sender: Arc<dyn MessageSender<M>>) -> Arc<dyn MessageSender<M>> {
+        match &self.timer {
+            Some(timer) => Arc::new(TimedSender { sender, timer: timer.clone() }),
+            None => sender,
+        }
     }
 
     /// # [`Fluxion::get`]
     /// Retrieves an actor reference capable of communicating using the given message via the given ID.
+    ///
+    /// # Errors
+    /// Returns [`SystemError::NotFound`] if the actor could not be found locally or by the delegate,
+    /// and [`SystemError::TypeMismatch`] if a local actor with the given id is not of type `A`.
+    /// Returns [`SystemError::ShuttingDown`] if the system has been shut down, and, for foreign actors,
+    /// [`SystemError::Disconnected`] if the delegate reports that it is not connected.
     #[cfg(feature = "serde")]
     pub async fn get<'a, A: Handler<M>, M: IndeterminateMessage>(&self,
-            #[cfg(feature="foreign")] id: impl Into<Identifier<'a>>,
-            #[cfg(not(feature="foreign"))] id: impl Into<Identifier>
-        ) -> Option<Arc<dyn MessageSender<M>>>
+            id: impl Into<Identifier<'a>>,
+        ) -> Result<Arc<dyn MessageSender<M>>, SystemError>
         where M::Result: serde::Serialize + for<'d> serde::Deserialize<'d> {
 
-        match id.into() {
-            Identifier::Local(id) => {
-                // Get the local ref and wrap in an arc
-                self.get_local::<A>(id).await
-                    .map(|h| Arc::new(h) as Arc<dyn MessageSender<M>>)
-            },
-            Identifier::LocalNamed(name) => {
-                // Get the actor's id by name
-                let id = self.get_actor_id(name).await?;
+        self.ensure_running()?;
 
-                // Get the local ref and wrap in an arc
-                self.get_local::<A>(id).await
-                    .map(|h| Arc::new(h) as Arc<dyn MessageSender<M>>)
+        match id.into() {
+            id @ (Identifier::Local(_) | Identifier::LocalNamed(_)) => {
+                self.get_local_sender::<A, M>(&id).await
             },
             #[cfg(feature = "foreign")]
             id => {
+                // Fail fast if the delegate can't reach other systems
+                if !self.delegate.is_connected().await {
+                    return Err(SystemError::Disconnected);
+                }
+
+                // Make sure that the foreign system supports the message
+                if let Identifier::Foreign(_, system) | Identifier::ForeignNamed(_, system) = id {
+                    if !self.delegate.supports(system, M::ID) {
+                        return Err(SystemError::Unsupported { message: M::ID });
+                    }
+                }
+
+                // Use the cache if it is enabled
+                if let (Some(cache), Some(key)) = (&self.foreign_cache, SenderCacheKey::new::<A, M>(&id)) {
+                    return cache.get_or_resolve(key, self.delegate.get_actor::<A, M>(id)).await
+                        .map(|sender| self.with_timer_of(sender))
+                        .ok_or(SystemError::NotFound);
+                }
+
                 // Send the request on to the delegate
                 self.delegate.get_actor::<A, M>(id).await
+                    .map(|sender| self.with_timer_of(sender))
+                    .ok_or(SystemError::NotFound)
             },
         }
     }
 
     /// # [`Fluxion::get`]
     /// Retrieves an actor reference capable of communicating using the given message via the given ID.
+    ///
+    /// # Errors
+    /// Returns [`SystemError::NotFound`] if the actor could not be found locally or by the delegate,
+    /// and [`SystemError::TypeMismatch`] if a local actor with the given id is not of type `A`.
+    /// Returns [`SystemError::ShuttingDown`] if the system has been shut down, and, for foreign actors,
+    /// [`SystemError::Disconnected`] if the delegate reports that it is not connected.
     #[cfg(not(feature = "serde"))]
     pub async fn get<'a, A: Handler<M>, M: IndeterminateMessage>(&self,
             id: impl Into<Identifier<'a>>,
-        ) -> Option<Arc<dyn MessageSender<M>>> {
+        ) -> Result<Arc<dyn MessageSender<M>>, SystemError> {
 
-        match id.into() {
-            Identifier::Local(id) => {
-                // Get the local ref and wrap in an arc
-                self.get_local::<A>(id).await
-                    .map(|h| Arc::new(h) as Arc<dyn MessageSender<M>>)
-            },
-            Identifier::LocalNamed(name) => {
-                // Get the actor's id by name
-                let id = self.get_actor_id(name).await?;
+        self.ensure_running()?;
 
-                // Get the local ref and wrap in an arc
-                self.get_local::<A>(id).await
-                    .map(|h| Arc::new(h) as Arc<dyn MessageSender<M>>)
+        match id.into() {
+            id @ (Identifier::Local(_) | Identifier::LocalNamed(_)) => {
+                self.get_local_sender::<A, M>(&id).await
             },
             #[cfg(feature = "foreign")]
             id => {
+                // Fail fast if the delegate can't reach other systems
+                if !self.delegate.is_connected().await {
+                    return Err(SystemError::Disconnected);
+                }
+
+                // Make sure that the foreign system supports the message
+                if let Identifier::Foreign(_, system) | Identifier::ForeignNamed(_, system) = id {
+                    if !self.delegate.supports(system, core::any::type_name::<M>()) {
+                        return Err(SystemError::Unsupported { message: core::any::type_name::<M>() });
+                    }
+                }
+
+                // Use the cache if it is enabled
+                if let (Some(cache), Some(key)) = (&self.foreign_cache, SenderCacheKey::new::<A, M>(&id)) {
+                    return cache.get_or_resolve(key, self.delegate.get_actor::<A, M>(id)).await
+                        .map(|sender| self.with_timer_of(sender))
+                        .ok_or(SystemError::NotFound);
+                }
+
                 // Send the request on to the delegate
                 self.delegate.get_actor::<A, M>(id).await
+                    .map(|sender| self.with_timer_of(sender))
+                    .ok_or(SystemError::NotFound)
             },
         }
     }
 
     /// # [`Fluxion::shutdown`]
-    /// Removes all actors from the system and deallocates the underlying slab.
+    /// Removes all actors from the system and deallocates the underlying slabs.
+    /// The system can not be used afterwards. See [`Fluxion::is_shutdown`].
+    ///
+    /// Actors are deinitialized in ascending order of their [`Actor::SHUTDOWN_PRIORITY`].
+    /// Actors with the same priority are deinitialized concurrently, and every one of them finishes
+    /// before any actor with a higher priority is deinitialized.
+    ///
+    /// ```
+    /// # use fluxion::{Actor, Fluxion};
+    /// # use std::sync::{Arc, Mutex};
+    /// # use tokio::sync::Barrier;
+    /// static ORDER: Mutex<Vec<i32>> = Mutex::new(Vec::new());
+    ///
+    /// struct Stage<const P: i32>;
+    ///
+    /// impl<const P: i32> Actor for Stage<P> {
+    ///     type Error = ();
+    ///     const SHUTDOWN_PRIORITY: i32 = P;
+    ///
+    ///     async fn deinitialize(&self) {
+    ///         ORDER.lock().unwrap().push(P);
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// system.add(Stage::<3>).await.unwrap();
+    /// system.add(Stage::<1>).await.unwrap();
+    /// system.add(Stage::<2>).await.unwrap();
+    ///
+    /// system.shutdown().await;
+    /// assert_eq!(*ORDER.lock().unwrap(), [1, 2, 3]);
+    ///
+    /// // Actors with the same priority can wait for each other while deinitializing
+    /// struct Peer(Arc<Barrier>);
+    ///
+    /// impl Actor for Peer {
+    ///     type Error = ();
+    ///
+    ///     async fn deinitialize(&self) {
+    ///         self.0.wait().await;
+    ///     }
+    /// }
+    ///
+    /// let system = Fluxion::new("peers", ());
+    /// let barrier = Arc::new(Barrier::new(2));
+    /// system.add(Peer(barrier.clone())).await.unwrap();
+    /// system.add(Peer(barrier)).await.unwrap();
+    ///
+    /// system.shutdown().await;
+    /// # });
+    /// ```
     /// 
     /// <div class = "info">
     /// Locks the underlying RwLock as write. This will block "management" functionalities such as adding, removing, and retrieving actors, but
     /// will not block any messages.
     /// </div>
     pub async fn shutdown(&self) {
-        self.slacktor.write().await.shutdown().await;
+        self.shutdown_inner(None).await;
+    }
+
+    /// # [`Fluxion::shutdown_timeout`]
+    /// Removes all actors from the system as if by [`Fluxion::shutdown`], but gives up on any actor whose
+    /// [`Actor::deinitialize`] takes longer than `per_actor`, measured using the system's [`Timer`].
+    /// Returns the ids of the actors that timed out.
+    ///
+    /// Actors that time out are removed from the system regardless, and their deinitialization is cancelled by dropping it.
+    /// Linked children created with [`Fluxion::fork_linked`] are shut down with the same timeout,
+    /// but the ids of their actors are not included in the result.
+    ///
+    /// ```
+    /// # use fluxion::{Actor, Fluxion, SystemError, Timer};
+    /// # use std::{future::Future, pin::Pin, time::Duration};
+    /// # struct TokioTimer;
+    /// # impl Timer for TokioTimer {
+    /// #     fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> { Box::pin(tokio::time::sleep(duration)) }
+    /// # }
+    /// struct Stuck;
+    ///
+    /// impl Actor for Stuck {
+    ///     type Error = ();
+    ///
+    ///     async fn deinitialize(&self) {
+    ///         std::future::pending::<()>().await;
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ()).with_timer(TokioTimer);
+    /// let stuck = system.add_id(Stuck).await.unwrap();
+    ///
+    /// let timed_out = system.shutdown_timeout(Duration::from_millis(10)).await.unwrap();
+    /// assert_eq!(timed_out, [stuck]);
+    /// assert_eq!(system.actor_count().await, 0);
+    ///
+    /// // Without a timer, the system is left running
+    /// let untimed = Fluxion::new("untimed", ());
+    /// assert_eq!(untimed.shutdown_timeout(Duration::from_millis(10)).await, Err(SystemError::NoScheduler));
+    /// assert!(!untimed.is_shutdown());
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`SystemError::NoScheduler`] without shutting anything down if the system has no timer.
+    ///
+    /// <div class = "info">
+    /// Locks the underlying RwLock as write. This will block "management" functionalities such as adding, removing, and retrieving actors, but
+    /// will not block any messages.
+    /// </div>
+    pub async fn shutdown_timeout(&self, per_actor: core::time::Duration) -> Result<Vec<u64>, SystemError> {
+        let timer = self.timer().ok_or(SystemError::NoScheduler)?;
+
+        Ok(self.shutdown_inner(Some((per_actor, timer))).await)
+    }
+
+    /// # [`Fluxion::is_shutdown`]
+    /// Returns true if the system has been shut down by [`Fluxion::shutdown`] or [`Fluxion::shutdown_timeout`],
+    /// including as a linked child of another system. Once shut down, a system can not be used again:
+    /// adding or retrieving actors fails with [`SystemError::ShuttingDown`], and there is nothing left to kill.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, AddError, Delegate, Fluxion, Handler, SystemError};
+    /// # #[actor] struct Worker;
+    /// # #[message] struct Work;
+    /// # impl Handler<Work> for Worker {
+    /// #     async fn handle_message<D: Delegate>(&self, _message: Work, _context: &ActorContext<D>) {}
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let id = system.add_named("worker", Worker).await.unwrap();
+    ///
+    /// system.shutdown().await;
+    /// assert!(system.is_shutdown());
+    ///
+    /// assert!(matches!(system.add(Worker).await, Err(AddError::System(SystemError::ShuttingDown))));
+    /// assert!(matches!(system.get_local::<Worker>(id).await, Err(SystemError::ShuttingDown)));
+    /// assert!(matches!(system.get_named::<Worker, Work>("worker").await, Err(SystemError::ShuttingDown)));
+    /// assert!(system.try_get_sender::<Worker, Work>(id).is_none());
+    /// assert!(!system.kill::<Worker>(id).await);
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn is_shutdown(&self) -> bool {
+        self.shut_down.load(Ordering::Acquire)
+    }
+
+    /// Returns the index of the shard that the actor with the given id is stored on
+    fn shard_index(&self, id: u64) -> usize {
+        // The remainder is less than the number of shards, so it fits in a usize
+        #[allow(clippy::cast_possible_truncation)]
+        let index = (id % self.shards.len() as u64) as usize;
+        index
+    }
+
+    /// Returns the shard that the actor with the given id is stored on
+    fn shard(&self, id: u64) -> &RwLock<Slacktor> {
+        &self.shards[self.shard_index(id)]
+    }
+
+    /// Returns the index of the shard that actors with the given identity are stored on.
+    /// This hashes the identity with FNV-1a, so that equivalent actors always share a shard.
+    fn identity_shard(&self, identity: &str) -> usize {
+        let hash = identity.bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+
+        self.shard_index(hash)
+    }
+
+    /// Locks every shard as read, in ascending order
+    async fn read_shards(&self) -> Vec<RwLockReadGuard<'_, Slacktor>> {
+        let mut shards = Vec::with_capacity(self.shards.len());
+        for shard in self.shards.iter() {
+            shards.push(shard.read().await);
+        }
+        shards
+    }
+
+    /// Locks every shard as write, in ascending order
+    async fn write_shards(&self) -> Vec<RwLockWriteGuard<'_, Slacktor>> {
+        let mut shards = Vec::with_capacity(self.shards.len());
+        for shard in self.shards.iter() {
+            shards.push(shard.write().await);
+        }
+        shards
+    }
+
+    /// Returns [`SystemError::ShuttingDown`] if the system has been shut down
+    fn ensure_running(&self) -> Result<(), SystemError> {
+        if self.is_shutdown() {
+            Err(SystemError::ShuttingDown)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Shuts the system down, bounding each actor's deinitialization if a timeout is given.
+    /// Returns the ids of the actors that timed out.
+    async fn shutdown_inner(&self, timeout: Option<(core::time::Duration, &dyn Timer)>) -> Vec<u64> {
+        let mut shards = self.write_shards().await;
+
+        // Refuse any further use of the system
+        self.shut_down.store(true, Ordering::Release);
+
+        // Forget every actor and its name. If deterministic ids are enabled,
+        // the id counter keeps counting from where it left off.
+        self.actor_ids.write().await.clear();
+        let entries = core::mem::take(&mut self.actors.write().await.entries);
+
+        // Deinitialize the actors in order of priority, deinitializing each group of actors with the same priority concurrently.
+        // Entries are already sorted by id, and the sort is stable, so each group's timed out actors are in order of id.
+        let mut entries = entries.into_iter().collect::<Vec<_>>();
+        entries.sort_by_key(|(_, entry)| entry.shutdown_priority);
+        let mut timed_out = Vec::new();
+        for group in entries.chunk_by(|(_, a), (_, b)| a.shutdown_priority == b.shutdown_priority) {
+            let deinits = group.iter().map(|(id, entry)| {
+                let deinit = (entry.deinit)(&shards[self.shard_index(*id)], entry.key);
+
+                async move {
+                    if let Some((per_actor, timer)) = timeout {
+                        with_deadline(deinit, timer.sleep(per_actor)).await.is_none().then_some(*id)
+                    } else {
+                        deinit.await;
+                        None
+                    }
+                }
+            });
+
+            timed_out.extend(join_all(deinits).await.into_iter().flatten());
+        }
+
+        // Deallocate the slabs. Every actor has already been deinitialized,
+        // so the shards are replaced instead of being shut down, which would deinitialize them again.
+        for shard in &mut shards {
+            **shard = Slacktor::new();
+        }
+        drop(shards);
+
+        // Wake anyone waiting for actors to stop
+        self.stopped.wake_all();
+
+        // Shut down linked children. The list is pruned and copied so that the lock is not held while they shut down.
+        let linked = {
+            let mut linked = self.linked.lock();
+            Self::prune_linked(&mut linked);
+            linked.clone()
+        };
+        for child in linked {
+            Box::pin(child.shutdown_inner(timeout)).await;
+        }
+
+        timed_out
+    }
+
+    /// # [`Fluxion::await_all_stopped`]
+    /// Waits until there are no actors left on the system, and every killed actor has finished deinitializing.
+    /// This does not stop any actors itself. Instead, it allows one task to wait for actors
+    /// that are being stopped by another, for example with [`Fluxion::shutdown`].
+    ///
+    /// ```
+    /// # use fluxion::{actor, Fluxion};
+    /// # #[actor] struct Worker;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// system.add(Worker).await.unwrap();
+    /// system.add(Worker).await.unwrap();
+    ///
+    /// let stopped = tokio::spawn({
+    ///     let system = system.clone();
+    ///     async move { system.await_all_stopped().await }
+    /// });
+    ///
+    /// system.shutdown().await;
+    /// stopped.await.unwrap();
+    /// # });
+    /// ```
+    ///
+    /// <div class = "warn">
+    /// If actors keep being added, this may wait forever.
+    /// </div>
+    pub async fn await_all_stopped(&self) {
+        loop {
+            // Start waiting before checking, so that any
+            // wakeups that occur during the check are not missed.
+            let wait = self.stopped.wait();
+
+            // Actors are killed with their shard locked as write,
+            // so holding every shard as read ensures that no actor is still deinitializing.
+            let shards = self.read_shards().await;
+            let empty = self.actors.read().await.entries.is_empty();
+            drop(shards);
+
+            if empty {
+                return;
+            }
+
+            // The queue is never closed, so this can't fail
+            let _ = wait.await;
+        }
     }
 }
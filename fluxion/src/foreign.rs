@@ -2,10 +2,14 @@
 //! This module provides traits and utilities for implementing foreign message handlers.
 
 #[cfg(feature="foreign")]
-use alloc::sync::Arc;
+use alloc::{boxed::Box, collections::BTreeMap, string::String, sync::{Arc, Weak}, vec::Vec};
+#[cfg(feature="foreign")]
+use core::any::{Any, TypeId};
+#[cfg(feature="foreign")]
+use maitake_sync::spin::Mutex;
 
 #[cfg(feature="foreign")]
-use crate::{Handler, Identifier, MessageSender, IndeterminateMessage};
+use crate::{Handler, Identifier, Message, MessageSendError, MessageSender, IndeterminateMessage};
 
 
 
@@ -26,6 +30,227 @@ pub trait Delegate: Send + Sync + 'static {
     #[cfg(all(feature="foreign", feature="serde"))]
     fn get_actor<A: Handler<M>, M: IndeterminateMessage>(&self, id: Identifier) -> impl core::future::Future<Output = Option<Arc<dyn MessageSender<M>>>> + Send
         where M::Result: serde::Serialize + for<'a> serde::Deserialize<'a>;
+
+    /// # [`Delegate::get_actors`]
+    /// Retrieves an [`ActorRef`] for each of the given foreign actors, in the same order as `ids`.
+    /// By default, this calls [`Delegate::get_actor`] once per actor. Delegates whose transport
+    /// can resolve many actors in a single request should override this to batch the lookups.
+    #[cfg(all(feature="foreign", not(feature="serde")))]
+    fn get_actors<A: Handler<M>, M: IndeterminateMessage>(&self, ids: &[Identifier]) -> impl core::future::Future<Output = Vec<Option<Arc<dyn MessageSender<M>>>>> + Send {
+        async move {
+            let mut actors = Vec::with_capacity(ids.len());
+            for id in ids {
+                actors.push(self.get_actor::<A, M>(*id).await);
+            }
+            actors
+        }
+    }
+
+    /// # [`Delegate::get_actors`]
+    /// Retrieves an [`ActorRef`] for each of the given foreign actors, in the same order as `ids`.
+    /// By default, this calls [`Delegate::get_actor`] once per actor. Delegates whose transport
+    /// can resolve many actors in a single request should override this to batch the lookups.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, Identifier, IndeterminateMessage, MessageSender};
+    /// # use serde::{Deserialize, Serialize};
+    /// # use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+    /// # #[actor] struct Shard;
+    /// # #[message(u64)] #[derive(Serialize, Deserialize)] struct Ping;
+    /// # impl Handler<Ping> for Shard {
+    /// #     async fn handle_message<D: Delegate>(&self, _message: Ping, context: &ActorContext<D>) -> u64 { context.get_id() }
+    /// # }
+    /// /// Resolves actors on another system, counting the requests made to it
+    /// struct BatchingDelegate {
+    ///     remote: Fluxion,
+    ///     requests: AtomicUsize,
+    /// }
+    ///
+    /// impl Delegate for BatchingDelegate {
+    ///     async fn get_actor<A: Handler<M>, M: IndeterminateMessage>(&self, id: Identifier<'_>) -> Option<Arc<dyn MessageSender<M>>>
+    ///         where M::Result: Serialize + for<'a> Deserialize<'a> {
+    ///         self.requests.fetch_add(1, Ordering::Relaxed);
+    ///         let Identifier::Foreign(id, _) = id else { return None };
+    ///         Some(Arc::new(self.remote.get_local::<A>(id).await.ok()?))
+    ///     }
+    ///
+    ///     async fn get_actors<A: Handler<M>, M: IndeterminateMessage>(&self, ids: &[Identifier<'_>]) -> Vec<Option<Arc<dyn MessageSender<M>>>>
+    ///         where M::Result: Serialize + for<'a> Deserialize<'a> {
+    ///         // A real transport would send every id in one request
+    ///         self.requests.fetch_add(1, Ordering::Relaxed);
+    ///         let mut actors = Vec::with_capacity(ids.len());
+    ///         for id in ids {
+    ///             let sender: Option<Arc<dyn MessageSender<M>>> = match id {
+    ///                 Identifier::Foreign(id, _) => self.remote.get_local::<A>(*id).await.ok().map(|a| Arc::new(a) as _),
+    ///                 _ => None,
+    ///             };
+    ///             actors.push(sender);
+    ///         }
+    ///         actors
+    ///     }
+    /// }
+    ///
+    /// /// Uses the default implementation of `get_actors`
+    /// struct UnbatchedDelegate(BatchingDelegate);
+    ///
+    /// impl Delegate for UnbatchedDelegate {
+    ///     async fn get_actor<A: Handler<M>, M: IndeterminateMessage>(&self, id: Identifier<'_>) -> Option<Arc<dyn MessageSender<M>>>
+    ///         where M::Result: Serialize + for<'a> Deserialize<'a> {
+    ///         self.0.get_actor::<A, M>(id).await
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let remote = Fluxion::new("remote", ());
+    /// let first = remote.add_id(Shard).await.unwrap();
+    /// let second = remote.add_id(Shard).await.unwrap();
+    /// let delegate = BatchingDelegate { remote, requests: AtomicUsize::new(0) };
+    ///
+    /// let ids = [
+    ///     Identifier::Foreign(second, "remote"),
+    ///     Identifier::Foreign(second + 1, "remote"),
+    ///     Identifier::Foreign(first, "remote"),
+    /// ];
+    /// let actors = delegate.get_actors::<Shard, Ping>(&ids).await;
+    ///
+    /// // The actors are returned in the order they were requested, using a single request
+    /// assert_eq!(delegate.requests.load(Ordering::Relaxed), 1);
+    /// assert_eq!(actors[0].as_ref().unwrap().send(Ping).await.unwrap(), second);
+    /// assert!(actors[1].is_none());
+    /// assert_eq!(actors[2].as_ref().unwrap().send(Ping).await.unwrap(), first);
+    ///
+    /// // Without the override, each actor is requested separately
+    /// let delegate = UnbatchedDelegate(delegate);
+    /// let actors = delegate.get_actors::<Shard, Ping>(&ids).await;
+    /// assert_eq!(delegate.0.requests.load(Ordering::Relaxed), 4);
+    /// assert_eq!(actors.iter().filter(|a| a.is_some()).count(), 2);
+    /// # });
+    /// ```
+    #[cfg(all(feature="foreign", feature="serde"))]
+    fn get_actors<A: Handler<M>, M: IndeterminateMessage>(&self, ids: &[Identifier]) -> impl core::future::Future<Output = Vec<Option<Arc<dyn MessageSender<M>>>>> + Send
+        where M::Result: serde::Serialize + for<'a> serde::Deserialize<'a> {
+        async move {
+            let mut actors = Vec::with_capacity(ids.len());
+            for id in ids {
+                actors.push(self.get_actor::<A, M>(*id).await);
+            }
+            actors
+        }
+    }
+
+    /// # [`Delegate::supports`]
+    /// Returns whether the given foreign system is able to receive the message with the given id.
+    /// [`Fluxion::get`](crate::Fluxion::get) checks this before asking the delegate for an actor, and returns
+    /// [`SystemError::Unsupported`](crate::SystemError::Unsupported) if it returns false.
+    /// Delegates that know the capabilities of other systems can use this to reject unsupported messages early,
+    /// instead of failing on the first send. By default, every message is assumed to be supported.
+    ///
+    /// If the `serde` feature is enabled, `message` is the message's [`MessageID::ID`](crate::MessageID::ID).
+    /// Otherwise, it is the message's type name, as returned by [`core::any::type_name`].
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")] {
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, Identifier, IndeterminateMessage, MessageSender, SystemError};
+    /// # use serde::{Deserialize, Serialize};
+    /// # use std::sync::Arc;
+    /// # #[actor] struct Image;
+    /// # #[message((), "image/rotate")] #[derive(Serialize, Deserialize)] struct Rotate;
+    /// # #[message((), "image/resize")] #[derive(Serialize, Deserialize)] struct Resize;
+    /// # impl Handler<Rotate> for Image {
+    /// #     async fn handle_message<D: Delegate>(&self, _message: Rotate, _context: &ActorContext<D>) {}
+    /// # }
+    /// # impl Handler<Resize> for Image {
+    /// #     async fn handle_message<D: Delegate>(&self, _message: Resize, _context: &ActorContext<D>) {}
+    /// # }
+    /// /// Resolves foreign actors on another system, which is running an older version that can't resize images
+    /// struct LegacyDelegate(Fluxion);
+    ///
+    /// impl Delegate for LegacyDelegate {
+    ///     async fn get_actor<A: Handler<M>, M: IndeterminateMessage>(&self, id: Identifier<'_>) -> Option<Arc<dyn MessageSender<M>>>
+    ///         where M::Result: Serialize + for<'a> Deserialize<'a> {
+    ///         let Identifier::Foreign(id, _) = id else { return None };
+    ///         Some(Arc::new(self.0.get_local::<A>(id).await.ok()?))
+    ///     }
+    ///
+    ///     fn supports(&self, system: &str, message: &str) -> bool {
+    ///         !(system == "legacy" && message == "image/resize")
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let legacy = Fluxion::new("legacy", ());
+    /// let image = legacy.add_id(Image).await.unwrap();
+    /// let system = Fluxion::new("system", LegacyDelegate(legacy));
+    ///
+    /// assert!(system.get::<Image, Rotate>(Identifier::Foreign(image, "legacy")).await.is_ok());
+    ///
+    /// let res = system.get::<Image, Resize>(Identifier::Foreign(image, "legacy")).await;
+    /// assert_eq!(res.err(), Some(SystemError::Unsupported { message: "image/resize" }));
+    /// # });
+    /// # }
+    /// ```
+    #[cfg(feature="foreign")]
+    fn supports(&self, system: &str, message: &str) -> bool {
+        let _ = (system, message);
+        true
+    }
+
+    /// # [`Delegate::is_connected`]
+    /// Returns whether the delegate's transport is currently usable.
+    /// [`Fluxion::get`](crate::Fluxion::get) checks this before retrieving a foreign actor, and returns
+    /// [`SystemError::Disconnected`](crate::SystemError::Disconnected) if it returns false, so that callers can fail fast or fall back
+    /// instead of queuing work against a dead transport. By default, the delegate is assumed to always be connected.
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")] {
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, Identifier, IndeterminateMessage, MessageSender, SystemError};
+    /// # use serde::{Deserialize, Serialize};
+    /// # use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+    /// # #[actor] struct Sensor;
+    /// # #[message(u64)] #[derive(Serialize, Deserialize)] struct Read;
+    /// # impl Handler<Read> for Sensor {
+    /// #     async fn handle_message<D: Delegate>(&self, _message: Read, _context: &ActorContext<D>) -> u64 { 42 }
+    /// # }
+    /// /// Resolves actors on another system over a link that may go down
+    /// struct LinkDelegate {
+    ///     remote: Fluxion,
+    ///     connected: AtomicBool,
+    /// }
+    ///
+    /// impl Delegate for LinkDelegate {
+    ///     async fn get_actor<A: Handler<M>, M: IndeterminateMessage>(&self, id: Identifier<'_>) -> Option<Arc<dyn MessageSender<M>>>
+    ///         where M::Result: Serialize + for<'a> Deserialize<'a> {
+    ///         let Identifier::Foreign(id, _) = id else { return None };
+    ///         Some(Arc::new(self.remote.get_local::<A>(id).await.ok()?))
+    ///     }
+    ///
+    ///     async fn is_connected(&self) -> bool {
+    ///         self.connected.load(Ordering::Acquire)
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let remote = Fluxion::new("remote", ());
+    /// let id = remote.add_id(Sensor).await.unwrap();
+    /// let system = Fluxion::new("system", LinkDelegate { remote, connected: AtomicBool::new(true) });
+    ///
+    /// let sensor = system.get::<Sensor, Read>(Identifier::Foreign(id, "remote")).await.unwrap();
+    /// assert_eq!(sensor.send(Read).await.unwrap(), 42);
+    ///
+    /// system.get_delegate().connected.store(false, Ordering::Release);
+    /// let res = system.get::<Sensor, Read>(Identifier::Foreign(id, "remote")).await;
+    /// assert_eq!(res.err(), Some(SystemError::Disconnected));
+    ///
+    /// // Local actors are not affected
+    /// let local = system.add_id(Sensor).await.unwrap();
+    /// assert!(system.get::<Sensor, Read>(Identifier::Local(local)).await.is_ok());
+    /// # });
+    /// # }
+    /// ```
+    #[cfg(feature="foreign")]
+    fn is_connected(&self) -> impl core::future::Future<Output = bool> + Send {
+        async { true }
+    }
 }
 
 // Delegate is implemented for () as a no-op
@@ -58,5 +283,172 @@ impl<D: Delegate> Delegate for alloc::sync::Arc<D> {
     fn get_actor<A: Handler<M>, M: IndeterminateMessage>(&self, id: Identifier) -> impl core::future::Future<Output = Option<Arc<dyn MessageSender<M>>>> + Send {
         D::get_actor::<A, M>(self, id)
     }
+
+    #[cfg(all(feature="foreign", feature="serde"))]
+    fn get_actors<A: Handler<M>, M: IndeterminateMessage>(&self, ids: &[Identifier]) -> impl core::future::Future<Output = Vec<Option<Arc<dyn MessageSender<M>>>>> + Send
+        where M::Result: serde::Serialize + for<'a> serde::Deserialize<'a> {
+        D::get_actors::<A, M>(self, ids)
+    }
+
+    #[cfg(all(feature="foreign", not(feature="serde")))]
+    fn get_actors<A: Handler<M>, M: IndeterminateMessage>(&self, ids: &[Identifier]) -> impl core::future::Future<Output = Vec<Option<Arc<dyn MessageSender<M>>>>> + Send {
+        D::get_actors::<A, M>(self, ids)
+    }
+
+    #[cfg(feature="foreign")]
+    fn supports(&self, system: &str, message: &str) -> bool {
+        D::supports(self, system, message)
+    }
+
+    #[cfg(feature="foreign")]
+    fn is_connected(&self) -> impl core::future::Future<Output = bool> + Send {
+        D::is_connected(self)
+    }
+}
+
+
+/// # [`SenderCacheKey`]
+/// Identifies a cached foreign sender by the actor it refers to,
+/// as well as the actor and message types it was retrieved for.
+#[cfg(feature="foreign")]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum SenderCacheKey {
+    /// An actor addressed by its id and system
+    Foreign(u64, String, TypeId),
+    /// An actor addressed by its name and system
+    ForeignNamed(String, String, TypeId),
+}
+
+#[cfg(feature="foreign")]
+impl SenderCacheKey {
+    /// Creates a cache key for the given identifier, actor, and message.
+    /// Returns [`None`] if the identifier does not refer to a foreign actor.
+    pub(crate) fn new<A: 'static, M: 'static>(id: &Identifier<'_>) -> Option<Self> {
+        let types = TypeId::of::<(A, M)>();
+
+        match id {
+            Identifier::Foreign(id, system) => Some(Self::Foreign(*id, String::from(*system), types)),
+            Identifier::ForeignNamed(name, system) => Some(Self::ForeignNamed(String::from(*name), String::from(*system), types)),
+            _ => None,
+        }
+    }
+}
+
+/// # [`SenderCache`]
+/// A least-recently-used cache of foreign senders retrieved from a [`Delegate`].
+/// Senders are evicted when they fail to send a message, as the actor
+/// they refer to has most likely become inaccessible.
+#[cfg(feature="foreign")]
+pub(crate) struct SenderCache {
+    /// The maximum number of senders to keep cached
+    capacity: usize,
+    /// The cached senders, alongside the tick at which they were last used.
+    /// Each sender is an `Arc<dyn MessageSender<M>>` for its message type.
+    /// This is a spinlock, as it is never held across an await point.
+    entries: Mutex<SenderCacheEntries>,
+}
+
+/// The contents of a [`SenderCache`]
+#[cfg(feature="foreign")]
+#[derive(Default)]
+struct SenderCacheEntries {
+    /// Incremented every time the cache is accessed
+    tick: u64,
+    /// The cached senders
+    senders: BTreeMap<SenderCacheKey, (u64, Box<dyn Any + Send + Sync>)>,
+}
+
+#[cfg(feature="foreign")]
+impl SenderCache {
+    /// Creates a new cache holding at most `capacity` senders
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(SenderCacheEntries::default()),
+        }
+    }
+
+    /// Retrieves the sender cached under the given key, or awaits `resolve` and caches its result.
+    /// The cache is not locked while `resolve` is running, so concurrent misses for the same key
+    /// each resolve a sender, and the last one to finish replaces the others.
+    pub(crate) async fn get_or_resolve<M: Message>(self: &Arc<Self>, key: SenderCacheKey,
+        resolve: impl core::future::Future<Output = Option<Arc<dyn MessageSender<M>>>>) -> Option<Arc<dyn MessageSender<M>>> {
+
+        // Check for a cached sender, marking it as recently used
+        {
+            let mut entries = self.entries.lock();
+            entries.tick += 1;
+            let tick = entries.tick;
+
+            if let Some((last_used, sender)) = entries.senders.get_mut(&key) {
+                *last_used = tick;
+                return sender.downcast_ref::<Arc<dyn MessageSender<M>>>().cloned();
+            }
+        }
+
+        // Resolve the sender and wrap it so that it evicts itself on failure
+        let sender: Arc<dyn MessageSender<M>> = Arc::new(CachedSender {
+            sender: resolve.await?,
+            cache: Arc::downgrade(self),
+            key: key.clone(),
+        });
+
+        if self.capacity == 0 {
+            return Some(sender);
+        }
+
+        let mut entries = self.entries.lock();
+
+        // Evict the least recently used sender if the cache is full
+        if entries.senders.len() >= self.capacity {
+            let oldest = entries.senders.iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(key, _)| key.clone());
+
+            if let Some(oldest) = oldest {
+                entries.senders.remove(&oldest);
+            }
+        }
+
+        let tick = entries.tick;
+        entries.senders.insert(key, (tick, Box::new(sender.clone())));
+
+        Some(sender)
+    }
+
+    /// Removes the sender cached under the given key, if any
+    fn invalidate(&self, key: &SenderCacheKey) {
+        self.entries.lock().senders.remove(key);
+    }
 }
 
+/// A [`MessageSender`] stored in a [`SenderCache`], which removes itself
+/// from the cache when a message fails to send.
+#[cfg(feature="foreign")]
+struct CachedSender<M: Message> {
+    /// The sender returned by the delegate
+    sender: Arc<dyn MessageSender<M>>,
+    /// The cache containing this sender.
+    /// This is weak, as the cache itself holds this sender.
+    cache: Weak<SenderCache>,
+    /// The key this sender is cached under
+    key: SenderCacheKey,
+}
+
+#[cfg(feature="foreign")]
+#[async_trait::async_trait]
+impl<M: Message> MessageSender<M> for CachedSender<M> {
+    async fn send(&self, message: M) -> Result<M::Result, MessageSendError> {
+        let res = self.sender.send(message).await;
+
+        // Any error is taken to mean that the sender is stale,
+        // so the next retrieval will go back to the delegate.
+        if res.is_err() {
+            if let Some(cache) = self.cache.upgrade() {
+                cache.invalidate(&self.key);
+            }
+        }
+
+        res
+    }
+}
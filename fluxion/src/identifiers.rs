@@ -6,7 +6,7 @@
 /// # [`Identifier`]
 /// Identifies an individual actor on a given system. There are two variants: one for actors on the current system, and one on a foreign system.
 /// These are called [`Identifier::Local`] and [`Identifier::Foreign`] respectively.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum Identifier<'a> {
     /// Identifies an actor on the current system. Contains the actor's id as a 64-bit integer.
     Local(u64),
@@ -20,23 +20,110 @@ pub enum Identifier<'a> {
     ForeignNamed(&'a str, &'a str),
 }
 
-#[cfg(feature = "foreign")]
-impl<'a> From<u64> for Identifier<'a> {
+impl From<u64> for Identifier<'_> {
     fn from(value: u64) -> Self {
         Identifier::Local(value)
     }
 }
 
-#[cfg(not(feature = "foreign"))]
-impl From<u64> for Identifier<'_> {
-    fn from(value: u64) -> Self {
-        Identifier::Local(value)
+/// Parses an identifier from a string.
+/// A plain id such as `"123"` is parsed as [`Identifier::Local`], and an id prefixed with its
+/// system, such as `"remote:123"`, is parsed as [`Identifier::Foreign`]. The id follows the last `:`.
+///
+/// This is implemented in place of [`FromStr`](core::str::FromStr), as the foreign system's id is borrowed from the string.
+///
+/// ```
+/// # use fluxion::{Identifier, IdentifierParseError};
+/// assert!(matches!(Identifier::try_from("123"), Ok(Identifier::Local(123))));
+/// # #[cfg(feature = "foreign")]
+/// assert!(matches!(Identifier::try_from("remote:123"), Ok(Identifier::Foreign(123, "remote"))));
+///
+/// let err = Identifier::try_from("remote:abc").unwrap_err();
+/// assert!(matches!(err, IdentifierParseError::InvalidId(_)));
+/// ```
+impl<'a> TryFrom<&'a str> for Identifier<'a> {
+    type Error = IdentifierParseError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        let Some((system, id)) = value.rsplit_once(':') else {
+            return value.parse().map(Identifier::Local).map_err(IdentifierParseError::InvalidId);
+        };
+
+        let id: u64 = id.parse().map_err(IdentifierParseError::InvalidId)?;
+
+        #[cfg(feature = "foreign")]
+        {
+            Ok(Identifier::Foreign(id, system))
+        }
+
+        #[cfg(not(feature = "foreign"))]
+        {
+            let _ = (system, id);
+            Err(IdentifierParseError::ForeignUnsupported)
+        }
+    }
+}
+
+/// # [`IdentifierParseError`]
+/// An error returned when an [`Identifier`] fails to be parsed from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IdentifierParseError {
+    /// The actor's id is not a valid 64-bit unsigned integer.
+    InvalidId(core::num::ParseIntError),
+    /// The string contains a system id, but the `foreign` feature is not enabled.
+    ForeignUnsupported,
+}
+
+impl core::fmt::Display for IdentifierParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            IdentifierParseError::InvalidId(e) => write!(f, "IdentifierParseError: invalid actor id: {e}"),
+            IdentifierParseError::ForeignUnsupported => write!(f, "IdentifierParseError: foreign identifiers require the foreign feature"),
+        }
+    }
+}
+
+impl core::error::Error for IdentifierParseError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            IdentifierParseError::InvalidId(e) => Some(e),
+            IdentifierParseError::ForeignUnsupported => None,
+        }
     }
 }
 
 /// # [`MessageID`]
 /// Every foreign message is required to have a unique ID.
 /// This is automatically populated by the `message` proc macro.
+///
+/// Messages without an id can still be sent to local actors, but can not be used with [`Fluxion::get`](crate::Fluxion::get)
+/// when the `serde` feature is enabled. Doing so reports that the message is missing the `message` attribute:
+///
+/// ```compile_fail,E0277
+/// # use fluxion::MessageID;
+/// // error[E0277]: `Plain` is not a message with an id
+/// struct Plain;
+///
+/// let _ = <Plain as MessageID>::ID;
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a message with an id",
+    label = "`{Self}` does not implement `MessageID`",
+    note = "annotate `{Self}` with `#[message]` to implement `MessageID`, which is required to send messages that may be foreign",
+)]
 pub trait MessageID {
     const ID: &'static str;
+
+    /// The name of the message's response type, as written in the `message` proc macro.
+    /// This is intended for tooling and debugging, and is not used to identify messages.
+    ///
+    /// ```
+    /// # use fluxion::{message, MessageID};
+    /// #[message(Option<Vec<u8>>)]
+    /// struct Lookup(u64);
+    ///
+    /// assert_eq!(Lookup::RESPONSE_NAME, "Option<Vec<u8>>");
+    /// ```
+    const RESPONSE_NAME: &'static str;
 }
\ No newline at end of file
@@ -0,0 +1,60 @@
+//! # Interceptors
+//! Interceptors observe every message handled by the actors on a system, without changes to the actors' handlers.
+//! This is useful for cross-cutting concerns such as logging and metrics.
+
+
+/// # [`Interceptor`]
+/// Called around every message handled by an actor on a system it is registered on with [`Fluxion::with_interceptor`](crate::Fluxion::with_interceptor).
+/// Both methods do nothing by default, so implementors only need to provide the ones they use.
+///
+/// Interceptors compose in registration order: [`Interceptor::before`] is called on each interceptor in the order they were registered,
+/// and [`Interceptor::after`] in the reverse order, so the first interceptor registered wraps all of the others.
+///
+/// `message` is the message's type name, as returned by [`core::any::type_name`].
+///
+/// ```
+/// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, Interceptor, MessageSender};
+/// # use std::sync::{Arc, Mutex};
+/// # #[actor] struct Worker;
+/// # #[message] struct Work;
+/// # impl Handler<Work> for Worker {
+/// #     async fn handle_message<D: Delegate>(&self, _message: Work, _context: &ActorContext<D>) {}
+/// # }
+/// struct Log(&'static str, Arc<Mutex<Vec<String>>>);
+///
+/// impl Interceptor for Log {
+///     fn before(&self, actor: u64, _message: &str) {
+///         self.1.lock().unwrap().push(format!("{} before {actor}", self.0));
+///     }
+///
+///     fn after(&self, actor: u64, _message: &str) {
+///         self.1.lock().unwrap().push(format!("{} after {actor}", self.0));
+///     }
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let log = Arc::new(Mutex::new(Vec::new()));
+/// let system = Fluxion::new("system", ())
+///     .with_interceptor(Log("outer", log.clone()))
+///     .with_interceptor(Log("inner", log.clone()));
+///
+/// let worker = system.add(Worker).await.unwrap();
+/// worker.send(Work).await.unwrap();
+///
+/// assert_eq!(*log.lock().unwrap(), ["outer before 0", "inner before 0", "inner after 0", "outer after 0"]);
+/// # });
+/// ```
+pub trait Interceptor: Send + Sync + 'static {
+    /// # [`Interceptor::before`]
+    /// Called before the actor with the given id handles a message.
+    fn before(&self, actor: u64, message: &str) {
+        let _ = (actor, message);
+    }
+
+    /// # [`Interceptor::after`]
+    /// Called after the actor with the given id has handled a message.
+    /// This is not called if the handler is cancelled, such as when the sender's future is dropped.
+    fn after(&self, actor: u64, message: &str) {
+        let _ = (actor, message);
+    }
+}
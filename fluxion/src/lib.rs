@@ -8,12 +8,69 @@
 
 extern crate alloc;
 
-pub use fluxion_macro::{message, actor};
+/// Implements [`Message`] and [`MessageID`] for a type, taking the message's result type as an argument,
+/// optionally followed by the message's id.
+/// Generic types are supported, and the result type may refer to the type's generic parameters.
+/// Every instantiation of a generic message shares the same id.
+///
+/// ```
+/// # use fluxion::{message, Message};
+/// #[message(Option<T>)]
+/// struct Take<T: Send + Sync + 'static>(T);
+///
+/// fn result_of<M: Message>(_message: &M) -> &'static str {
+///     core::any::type_name::<M::Result>()
+/// }
+///
+/// assert_eq!(result_of(&Take(1u8)), "core::option::Option<u8>");
+/// ```
+pub use fluxion_macro::message;
+/// Derives [`Message`] and [`MessageID`] for a type, as an alternative to [`macro@message`].
+/// The result type and id are given by the optional `#[fluxion(result = Type, id = "name")]` helper attribute,
+/// and default to `()` and the type's path respectively. The generated implementations are the same as those of [`macro@message`].
+///
+/// ```
+/// # use fluxion::{message, Message, MessageID};
+/// #[derive(Message)]
+/// #[fluxion(result = Vec<u8>, id = "read")]
+/// struct Read;
+///
+/// #[message(Vec<u8>, "read")]
+/// struct ReadAttribute;
+///
+/// assert_eq!(Read::ID, ReadAttribute::ID);
+/// assert_eq!(Read::RESPONSE_NAME, ReadAttribute::RESPONSE_NAME);
+/// let _: <Read as Message>::Result = <ReadAttribute as Message>::Result::new();
+/// ```
+pub use fluxion_macro::Message;
+/// Implements [`Actor`] for a type, optionally taking the actor's error type as an argument.
+/// Generic types are supported, and the error type may refer to the type's generic parameters.
+///
+/// ```
+/// # use fluxion::{actor, Fluxion};
+/// # use std::collections::BTreeMap;
+/// #[derive(Debug)]
+/// struct CacheError<K>(K);
+///
+/// #[actor(CacheError<K>)]
+/// struct Cache<K, V> where K: Ord + Send + Sync + 'static, V: Send + Sync + 'static {
+///     entries: BTreeMap<K, V>,
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let system = Fluxion::new("system", ());
+/// system.add(Cache::<u64, String> { entries: BTreeMap::new() }).await.unwrap();
+/// # });
+/// ```
+pub use fluxion_macro::actor;
 pub use const_format::concatcp;
 
 mod fluxion;
 pub use fluxion::*;
 
+mod error;
+pub use error::*;
+
 mod identifiers;
 pub use identifiers::*;
 
@@ -29,5 +86,30 @@ pub use references::*;
 mod foreign;
 pub use foreign::*;
 
+mod timer;
+pub use timer::*;
+
+mod schedule;
+pub use schedule::*;
+
+mod interceptor;
+pub use interceptor::*;
+
+mod metrics;
+pub use metrics::*;
+
+#[cfg(feature = "otel")]
+mod otel;
+#[cfg(feature = "otel")]
+pub use otel::*;
+
+#[cfg(feature = "serde")]
+mod codec;
+#[cfg(feature = "serde")]
+pub use codec::*;
+
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
 
 pub use slacktor::Message;
\ No newline at end of file
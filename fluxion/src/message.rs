@@ -8,25 +8,158 @@ use crate::MessageID;
 
 /// # [`MessageSendError`]
 /// An error type that might be returned during a message send.
+/// This error is `Send + Sync + 'static`, as are all of its sources, so it can be wrapped
+/// by application error types or boxed as a `Box<dyn Error + Send + Sync>`.
+///
+/// ```
+/// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSendError, MessageSender, ValidationError};
+/// # use std::error::Error;
+/// # #[actor] struct Store;
+/// # #[message] struct Put(u64);
+/// # impl Handler<Put> for Store {
+/// #     async fn handle_message<D: Delegate>(&self, _message: Put, _context: &ActorContext<D>) {}
+/// #     fn validate(message: &Put) -> Result<(), ValidationError> {
+/// #         if message.0 == 0 { Err(ValidationError::new("zero")) } else { Ok(()) }
+/// #     }
+/// # }
+/// #[derive(Debug, thiserror::Error)]
+/// enum AppError {
+///     #[error("failed to reach the store")]
+///     Send(#[from] MessageSendError),
+/// }
+///
+/// async fn put(store: &dyn MessageSender<Put>, value: u64) -> Result<(), AppError> {
+///     store.send(Put(value)).await?;
+///     Ok(())
+/// }
+///
+/// async fn put_anyhow(store: &dyn MessageSender<Put>, value: u64) -> anyhow::Result<()> {
+///     store.send(Put(value)).await?;
+///     Ok(())
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let system = Fluxion::new("system", ());
+/// let store = system.add(Store).await.unwrap();
+///
+/// // With thiserror, the send error is kept as the source
+/// let error = put(&store, 0).await.unwrap_err();
+/// assert!(matches!(error, AppError::Send(MessageSendError::Validation(_))));
+/// assert!(error.source().unwrap().to_string().starts_with("MessageSendError"));
+///
+/// // With anyhow, the send error can be recovered by downcasting
+/// let error = put_anyhow(&store, 0).await.unwrap_err();
+/// assert!(matches!(error.downcast_ref::<MessageSendError>(), Some(MessageSendError::Validation(_))));
+///
+/// put(&store, 1).await.unwrap();
+/// put_anyhow(&store, 1).await.unwrap();
+///
+/// let boxed: Box<dyn Error + Send + Sync> = Box::new(MessageSendError::ActorNotFound);
+/// assert_eq!(boxed.to_string(), "MessageSendError: actor not found");
+/// # });
+/// ```
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum MessageSendError {
     #[cfg(feature = "serde")]
     SerializationError {
         message: alloc::string::String,
-        source: alloc::boxed::Box<dyn core::error::Error>,
+        source: alloc::boxed::Box<dyn core::error::Error + Send + Sync>,
     },
     #[cfg(feature = "serde")]
     DeserializationError {
         message: alloc::string::String,
-        source: alloc::boxed::Box<dyn core::error::Error>,
+        source: alloc::boxed::Box<dyn core::error::Error + Send + Sync>,
     },
     #[cfg(feature = "foreign")]
     DelegateError {
         message: alloc::string::String,
-        source: alloc::boxed::Box<dyn core::error::Error>,
+        source: alloc::boxed::Box<dyn core::error::Error + Send + Sync>,
     },
-    UnknownError(alloc::boxed::Box<dyn Error>),
+    /// A serialized message exceeded the maximum payload size accepted by a delegate,
+    /// and was rejected before being sent.
+    ///
+    /// ```
+    /// # #[cfg(feature = "foreign")] {
+    /// # use fluxion::{actor, message, Actor, ActorContext, Delegate, Fluxion, Handler, Identifier, IndeterminateMessage, LocalRef, Message, MessageSendError, MessageSender};
+    /// # use serde::{Deserialize, Serialize};
+    /// # use std::sync::Arc;
+    /// # #[actor] struct Store;
+    /// #[message(usize)]
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Upload(Vec<u8>);
+    /// # impl Handler<Upload> for Store {
+    /// #     async fn handle_message<D: Delegate>(&self, message: Upload, _context: &ActorContext<D>) -> usize { message.0.len() }
+    /// # }
+    ///
+    /// /// Serializes messages before forwarding them, rejecting any larger than `limit` bytes
+    /// struct LimitedSender<A: Actor, M> {
+    ///     actor: LocalRef<A>,
+    ///     limit: usize,
+    ///     _message: std::marker::PhantomData<M>,
+    /// }
+    ///
+    /// #[async_trait::async_trait]
+    /// impl<A: Handler<M>, M: Message + Serialize + for<'a> Deserialize<'a>> MessageSender<M> for LimitedSender<A, M> {
+    ///     async fn send(&self, message: M) -> Result<M::Result, MessageSendError> {
+    ///         let payload = bincode::serialize(&message).unwrap();
+    ///         if payload.len() > self.limit {
+    ///             return Err(MessageSendError::PayloadTooLarge { size: payload.len(), limit: self.limit });
+    ///         }
+    ///         self.actor.send(bincode::deserialize(&payload).unwrap()).await
+    ///     }
+    /// }
+    ///
+    /// struct LimitedDelegate(Fluxion);
+    ///
+    /// impl Delegate for LimitedDelegate {
+    ///     async fn get_actor<A: Handler<M>, M: IndeterminateMessage>(&self, id: Identifier<'_>) -> Option<Arc<dyn MessageSender<M>>>
+    ///         where M::Result: Serialize + for<'a> Deserialize<'a> {
+    ///         let Identifier::Foreign(id, _) = id else { return None };
+    ///         let actor = self.0.get_local::<A>(id).await.ok()?;
+    ///         Some(Arc::new(LimitedSender { actor, limit: 1024, _message: std::marker::PhantomData }))
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let remote = Fluxion::new("remote", ());
+    /// let id = remote.add_id(Store).await.unwrap();
+    /// let system = Fluxion::new("system", LimitedDelegate(remote));
+    /// let store = system.get::<Store, Upload>(Identifier::Foreign(id, "remote")).await.unwrap();
+    ///
+    /// assert_eq!(store.send(Upload(vec![0; 512])).await.unwrap(), 512);
+    ///
+    /// let res = store.send(Upload(vec![0; 4096])).await;
+    /// assert!(matches!(res, Err(MessageSendError::PayloadTooLarge { size, limit: 1024 }) if size > 4096));
+    /// # });
+    /// # }
+    /// ```
+    #[cfg(feature = "serde")]
+    PayloadTooLarge {
+        /// The size of the serialized message, in bytes
+        size: usize,
+        /// The maximum payload size, in bytes
+        limit: usize,
+    },
+    /// The actor did not respond before the deadline passed to [`MessageSender::request_timeout`](crate::MessageSender::request_timeout).
+    Timeout,
+    /// [`MessageSender::request_timeout`](crate::MessageSender::request_timeout) was called on a sender with no [`Timer`](crate::Timer) to measure the timeout.
+    /// Senders retrieved from a system use the timer set with [`Fluxion::with_timer`](crate::Fluxion::with_timer).
+    NoTimer,
+    /// The target actor could not be reached.
+    /// Delegates should return this when the transport backing a sender has closed,
+    /// so that callers know to re-resolve the actor via [`crate::Fluxion::get`].
+    ActorNotFound,
+    /// The target actor already had as many messages in flight as its [`Actor::MAILBOX_CAPACITY`](crate::Actor::MAILBOX_CAPACITY) allows.
+    /// This is only returned by non-blocking sends, such as [`LocalRef::try_send`](crate::LocalRef::try_send).
+    MailboxFull,
+    /// The message was rejected by the target actor's [`Handler::validate`](crate::Handler::validate),
+    /// and was never handled.
+    Validation(ValidationError),
+    /// The message's type is not one that the sender can send.
+    /// This is returned by [`AnySender::send_any`](crate::AnySender::send_any).
+    Unhandled,
+    UnknownError(alloc::boxed::Box<dyn Error + Send + Sync>),
 }
 
 impl core::fmt::Display for MessageSendError {
@@ -38,6 +171,14 @@ impl core::fmt::Display for MessageSendError {
             MessageSendError::DeserializationError { message, source: _ } => message.clone(),
             #[cfg(feature = "foreign")]
             MessageSendError::DelegateError { message, source: _ } => message.clone(),
+            #[cfg(feature = "serde")]
+            MessageSendError::PayloadTooLarge { size, limit } => alloc::format!("payload of {size} bytes exceeds the limit of {limit} bytes"),
+            MessageSendError::Timeout => alloc::string::String::from("timed out waiting for a response"),
+            MessageSendError::NoTimer => alloc::string::String::from("no timer to measure the timeout with"),
+            MessageSendError::ActorNotFound => alloc::string::String::from("actor not found"),
+            MessageSendError::MailboxFull => alloc::string::String::from("mailbox full"),
+            MessageSendError::Validation(e) => alloc::format!("{e}"),
+            MessageSendError::Unhandled => alloc::string::String::from("message type not handled"),
             MessageSendError::UnknownError(e) => alloc::format!("{e}"),
         };
 
@@ -54,19 +195,54 @@ impl core::error::Error for MessageSendError {
             Self::DeserializationError { message: _, source } => Some(source.as_ref()),
             #[cfg(feature = "foreign")]
             Self::DelegateError { message: _, source } => Some(source.as_ref()),
+            #[cfg(feature = "serde")]
+            Self::PayloadTooLarge { .. } => None,
+            Self::Timeout | Self::NoTimer | Self::ActorNotFound | Self::MailboxFull | Self::Unhandled => None,
+            Self::Validation(e) => Some(e),
             Self::UnknownError(e) => Some(e.as_ref()),
         }
     }
+}
+
+/// # [`ValidationError`]
+/// Returned by [`Handler::validate`](crate::Handler::validate) when a message is rejected,
+/// carrying the reason it was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    reason: alloc::string::String,
+}
+
+impl ValidationError {
+    /// # [`ValidationError::new`]
+    /// Creates a new validation error with the given reason
+    pub fn new(reason: impl Into<alloc::string::String>) -> Self {
+        Self { reason: reason.into() }
+    }
 
-    fn description(&self) -> &str {
-        "description() is deprecated; use Display"
+    /// # [`ValidationError::reason`]
+    /// Retrieves the reason the message was rejected
+    #[must_use]
+    pub fn reason(&self) -> &str {
+        &self.reason
     }
 }
 
+impl core::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "ValidationError: {}", self.reason)
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
 /// # [`IndeterminateMessage`]
 /// An indeterminate message is a message for which it has not yet been determined whether it will be serialized.
 /// Because of this, indeterminate messages require serde traits to be implemented, which is not the case with local messages.
 #[cfg(feature = "serde")]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can not be sent to actors that may be foreign",
+    note = "messages that may be foreign must be annotated with `#[message]`, and both they and their responses must implement `Serialize` and `Deserialize`",
+)]
 pub trait IndeterminateMessage: Message + MessageID + serde::Serialize + for<'a> serde::Deserialize<'a> 
 where Self: Message + serde::Serialize + for<'a> serde::Deserialize<'a>,
     Self::Result: serde::Serialize + for<'a> serde::Deserialize<'a>{}
@@ -0,0 +1,200 @@
+//! # Metrics
+//! Fluxion does not depend on any metrics crate. Instead, applications can install a [`Metrics`]
+//! implementation on a system, which is notified about messages and can export them however it likes.
+
+use core::time::Duration;
+#[cfg(feature = "metrics")]
+use core::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "metrics")]
+use alloc::collections::BTreeMap;
+
+use crate::MessageSendError;
+
+
+/// # [`Metrics`]
+/// Receives measurements about the messages sent to and handled by the actors on a system it is installed on with [`Fluxion::with_metrics`](crate::Fluxion::with_metrics).
+/// Every method does nothing by default, so implementors only need to provide the ones they use.
+///
+/// `message` is the message's type name, as returned by [`core::any::type_name`].
+///
+/// ```
+/// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, Metrics, MessageSender, Timer};
+/// # use std::{future::Future, pin::Pin, sync::atomic::{AtomicU64, Ordering}, time::{Duration, Instant}};
+/// # #[actor] struct Worker;
+/// # #[message] struct Work;
+/// # impl Handler<Work> for Worker {
+/// #     async fn handle_message<D: Delegate>(&self, _message: Work, _context: &ActorContext<D>) {}
+/// # }
+/// struct StdTimer(Instant);
+///
+/// impl Timer for StdTimer {
+///     fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+///         Box::pin(tokio::time::sleep(duration))
+///     }
+///
+///     fn now(&self) -> Option<Duration> {
+///         Some(self.0.elapsed())
+///     }
+/// }
+///
+/// static HANDLED: AtomicU64 = AtomicU64::new(0);
+///
+/// struct Counter;
+///
+/// impl Metrics for Counter {
+///     fn on_message_handled(&self, _actor: u64, _message: &str, elapsed: Option<Duration>) {
+///         assert!(elapsed.is_some());
+///         HANDLED.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let system = Fluxion::new("system", ())
+///     .with_timer(StdTimer(Instant::now()))
+///     .with_metrics(Counter);
+///
+/// let worker = system.add(Worker).await.unwrap();
+/// worker.send(Work).await.unwrap();
+/// worker.send(Work).await.unwrap();
+///
+/// assert_eq!(HANDLED.load(Ordering::Relaxed), 2);
+/// # });
+/// ```
+pub trait Metrics: Send + Sync + 'static {
+    /// # [`Metrics::on_message_handled`]
+    /// Called after the actor with the given id has handled a message.
+    /// `elapsed` is the time the handler took, or [`None`] if the system's [`Timer`](crate::Timer) can't measure it,
+    /// as reported by [`Timer::now`](crate::Timer::now), or if the system has no timer.
+    fn on_message_handled(&self, actor: u64, message: &str, elapsed: Option<Duration>) {
+        let _ = (actor, message, elapsed);
+    }
+
+    /// # [`Metrics::on_send_error`]
+    /// Called when a message sent to the local actor with the given id through a [`LocalRef`](crate::LocalRef)
+    /// is rejected before being handled, such as by [`Handler::validate`](crate::Handler::validate).
+    fn on_send_error(&self, actor: u64, message: &str, error: &MessageSendError) {
+        let _ = (actor, message, error);
+    }
+
+    /// # [`Metrics::on_budget_exceeded`]
+    /// Called after the actor with the given id took longer than its [`Handler::TIME_BUDGET`](crate::Handler::TIME_BUDGET) to handle a message.
+    /// `elapsed` is the time the handler took, which is also passed to [`Metrics::on_message_handled`].
+    fn on_budget_exceeded(&self, actor: u64, message: &str, budget: Duration, elapsed: Duration) {
+        let _ = (actor, message, budget, elapsed);
+    }
+
+    /// # [`Metrics::on_message_started`]
+    /// Called when the actor with the given id starts handling a message.
+    /// Every call is followed by a call to [`Metrics::on_message_finished`], even if the handler is cancelled,
+    /// so the two can be used to count the messages in flight.
+    #[cfg(feature = "metrics")]
+    fn on_message_started(&self, actor: u64, message: &str) {
+        let _ = (actor, message);
+    }
+
+    /// # [`Metrics::on_message_finished`]
+    /// Called when the actor with the given id stops handling a message, either because its handler returned or because it was cancelled.
+    #[cfg(feature = "metrics")]
+    fn on_message_finished(&self, actor: u64, message: &str) {
+        let _ = (actor, message);
+    }
+}
+
+/// # [`NoopMetrics`]
+/// A [`Metrics`] implementation that discards every measurement.
+/// Systems behave as if this is installed until [`Fluxion::with_metrics`](crate::Fluxion::with_metrics) is called.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopMetrics;
+
+impl Metrics for NoopMetrics {}
+
+/// # [`SystemMetrics`]
+/// Counters aggregated over every live actor on a system, as returned by [`Fluxion::metrics_snapshot`](crate::Fluxion::metrics_snapshot).
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SystemMetrics {
+    /// The number of messages handled by the live actors
+    pub total_handled: u64,
+    /// The number of messages currently being handled by the live actors
+    pub total_in_flight: u64,
+    /// The number of live actors
+    pub actor_count: usize,
+    /// The counters of the live actors, summed per actor type name
+    pub by_type: BTreeMap<&'static str, ActorMetrics>,
+}
+
+/// # [`ActorMetrics`]
+/// Counters summed over the live actors of one type, as part of a [`SystemMetrics`].
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ActorMetrics {
+    /// The number of live actors of this type
+    pub actors: usize,
+    /// The number of messages handled by these actors
+    pub handled: u64,
+    /// The number of messages currently being handled by these actors
+    pub in_flight: u64,
+}
+
+/// The counters kept for every actor when the `metrics` feature is enabled.
+/// These are shared between the actor's context, which updates them, and its entry on the system, which reads them.
+#[cfg(feature = "metrics")]
+#[derive(Default)]
+pub(crate) struct ActorCounters {
+    /// The number of messages the actor has handled
+    handled: AtomicU64,
+    /// The number of messages the actor is currently handling
+    in_flight: AtomicU64,
+}
+
+#[cfg(feature = "metrics")]
+impl ActorCounters {
+    /// Counts a message as in flight until the returned guard is dropped, reporting it to `metrics` if given.
+    /// The message is counted as handled if [`InFlight::finish`] is called first.
+    pub(crate) fn begin<'a>(&'a self, metrics: Option<&'a dyn Metrics>, actor: u64, message: &'static str) -> InFlight<'a> {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        if let Some(metrics) = metrics {
+            metrics.on_message_started(actor, message);
+        }
+
+        InFlight { counters: self, metrics, actor, message }
+    }
+
+    /// Returns the number of messages handled, and the number in flight
+    pub(crate) fn load(&self) -> (u64, u64) {
+        (self.handled.load(Ordering::Relaxed), self.in_flight.load(Ordering::Relaxed))
+    }
+}
+
+/// Counts a message as in flight while it is alive, so that handlers that are cancelled are not counted forever
+#[cfg(feature = "metrics")]
+pub(crate) struct InFlight<'a> {
+    /// The counters of the actor handling the message
+    counters: &'a ActorCounters,
+    /// The metrics to report the end of the message to, if any
+    metrics: Option<&'a dyn Metrics>,
+    /// The id of the actor handling the message
+    actor: u64,
+    /// The message's type name
+    message: &'static str,
+}
+
+#[cfg(feature = "metrics")]
+impl InFlight<'_> {
+    /// Counts the message as handled
+    pub(crate) fn finish(self) {
+        self.counters.handled.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.counters.in_flight.fetch_sub(1, Ordering::Relaxed);
+        if let Some(metrics) = self.metrics {
+            metrics.on_message_finished(self.actor, self.message);
+        }
+    }
+}
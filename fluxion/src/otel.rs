@@ -0,0 +1,137 @@
+//! # OpenTelemetry
+//! Exports the measurements reported to [`Metrics`] through the [`opentelemetry`] metrics API,
+//! so that Fluxion can be observed by any OpenTelemetry meter provider.
+//! See the `otel` example for a complete setup.
+
+use alloc::string::ToString;
+use core::time::Duration;
+
+use opentelemetry::{metrics::{Counter, Histogram, Meter, UpDownCounter}, KeyValue};
+
+use crate::{MessageSendError, Metrics};
+
+
+/// # [`OtelMetrics`]
+/// Records the messages handled by a system's actors as OpenTelemetry instruments created from a [`Meter`]:
+///
+/// | Instrument | Kind | Recorded |
+/// |------------|------|----------|
+/// | `fluxion.messages.handled` | counter | when a handler returns |
+/// | `fluxion.messages.in_flight` | up-down counter | while a handler is running, until it returns or is cancelled |
+/// | `fluxion.message.duration` | histogram, in seconds | when a handler returns, if the system's [`Timer`](crate::Timer) can measure it |
+/// | `fluxion.send.errors` | counter | when a send is rejected before being handled |
+///
+/// Every measurement has a `message` attribute containing the message's type name.
+/// The instruments are updated once this is installed with [`Fluxion::with_metrics`](crate::Fluxion::with_metrics).
+/// Messages in flight are counted by the same cancel-safe counters that back [`Fluxion::metrics_snapshot`](crate::Fluxion::metrics_snapshot).
+///
+/// ```
+/// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSender, OtelMetrics};
+/// # use opentelemetry::metrics::MeterProvider;
+/// # use opentelemetry_sdk::metrics::{data::{AggregatedMetrics, MetricData}, InMemoryMetricExporter, PeriodicReader, SdkMeterProvider};
+/// # use std::time::Duration;
+/// # #[actor] struct Worker;
+/// # #[message] struct Work;
+/// # #[message] struct Hang;
+/// # impl Handler<Work> for Worker {
+/// #     async fn handle_message<D: Delegate>(&self, _message: Work, _context: &ActorContext<D>) {}
+/// # }
+/// # impl Handler<Hang> for Worker {
+/// #     async fn handle_message<D: Delegate>(&self, _message: Hang, _context: &ActorContext<D>) { std::future::pending::<()>().await }
+/// # }
+/// let exporter = InMemoryMetricExporter::default();
+/// let provider = SdkMeterProvider::builder()
+///     .with_reader(PeriodicReader::builder(exporter.clone()).build())
+///     .build();
+/// let otel = OtelMetrics::new(&provider.meter("fluxion"));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let system = Fluxion::new("system", ()).with_metrics(otel);
+///
+/// let worker = system.add(Worker).await.unwrap();
+/// worker.send(Work).await.unwrap();
+/// worker.send(Work).await.unwrap();
+///
+/// // A send that is cancelled mid-handler is no longer counted as in flight
+/// let hanging = tokio::spawn(async move { worker.send(Hang).await });
+/// tokio::time::sleep(Duration::from_millis(20)).await;
+/// hanging.abort();
+/// let _ = hanging.await;
+/// # });
+///
+/// provider.force_flush().unwrap();
+/// let exported = exporter.get_finished_metrics().unwrap();
+/// let metrics: Vec<_> = exported.iter()
+///     .flat_map(|resource| resource.scope_metrics())
+///     .flat_map(|scope| scope.metrics())
+///     .collect();
+///
+/// let handled = metrics.iter().find(|metric| metric.name() == "fluxion.messages.handled").unwrap();
+/// let AggregatedMetrics::U64(MetricData::Sum(handled)) = handled.data() else { panic!() };
+/// assert_eq!(handled.data_points().map(|point| point.value()).sum::<u64>(), 2);
+///
+/// let in_flight = metrics.iter().find(|metric| metric.name() == "fluxion.messages.in_flight").unwrap();
+/// let AggregatedMetrics::I64(MetricData::Sum(in_flight)) = in_flight.data() else { panic!() };
+/// assert_eq!(in_flight.data_points().map(|point| point.value()).sum::<i64>(), 0);
+/// ```
+#[derive(Clone)]
+pub struct OtelMetrics {
+    /// Counts the messages handled
+    handled: Counter<u64>,
+    /// Counts the messages being handled
+    in_flight: UpDownCounter<i64>,
+    /// Records how long handlers took
+    duration: Histogram<f64>,
+    /// Counts the sends rejected before being handled
+    send_errors: Counter<u64>,
+}
+
+impl OtelMetrics {
+    /// # [`OtelMetrics::new`]
+    /// Creates the instruments on the given meter.
+    #[must_use]
+    pub fn new(meter: &Meter) -> Self {
+        Self {
+            handled: meter.u64_counter("fluxion.messages.handled")
+                .with_description("Messages handled by actors")
+                .build(),
+            in_flight: meter.i64_up_down_counter("fluxion.messages.in_flight")
+                .with_description("Messages currently being handled by actors")
+                .build(),
+            duration: meter.f64_histogram("fluxion.message.duration")
+                .with_description("Time taken by actors to handle messages")
+                .with_unit("s")
+                .build(),
+            send_errors: meter.u64_counter("fluxion.send.errors")
+                .with_description("Sends rejected before being handled")
+                .build(),
+        }
+    }
+}
+
+/// The attributes recorded with every measurement about the given message
+fn attributes(message: &str) -> [KeyValue; 1] {
+    [KeyValue::new("message", message.to_string())]
+}
+
+impl Metrics for OtelMetrics {
+    fn on_message_handled(&self, _actor: u64, message: &str, elapsed: Option<Duration>) {
+        let attributes = attributes(message);
+        self.handled.add(1, &attributes);
+        if let Some(elapsed) = elapsed {
+            self.duration.record(elapsed.as_secs_f64(), &attributes);
+        }
+    }
+
+    fn on_send_error(&self, _actor: u64, message: &str, _error: &MessageSendError) {
+        self.send_errors.add(1, &attributes(message));
+    }
+
+    fn on_message_started(&self, _actor: u64, message: &str) {
+        self.in_flight.add(1, &attributes(message));
+    }
+
+    fn on_message_finished(&self, _actor: u64, message: &str) {
+        self.in_flight.add(-1, &attributes(message));
+    }
+}
@@ -4,8 +4,10 @@
 
 
 
-use crate::{Actor, ActorWrapper, Delegate, Handler, Message, MessageSendError};
-use alloc::boxed::Box;
+use crate::{timer::with_deadline, Actor, ActorWrapper, Delegate, Fluxion, Handler, Message, MessageSendError, Metrics, Timer};
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc};
+use core::{any::{Any, TypeId}, future::Future, pin::Pin};
+use maitake_sync::Semaphore;
 
 /// # [`ActorRef`]
 /// This trait provides methods for actors to communicate with and control each other.
@@ -16,6 +18,33 @@ pub trait ActorRef<A: Actor> {}
 /// This trait is only necessary because traits with generic methods are not object safe,
 /// and we need a way to be generic over multiple types of [`ActorRef`] at once.
 /// Sadly, [`async_trait`] is also required for this trait as async fns in traits are not yet object safe either.
+///
+/// Messages are moved into the actor when sent, so neither messages nor their responses are required to implement [`Clone`].
+/// This is true of every API that sends a message to a single actor, including [`LocalRef`], [`Fluxion::get`], and [`Pipe`].
+///
+/// ```
+/// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, Identifier, MessageSender};
+/// # #[actor] struct Sink;
+/// // Neither the message nor its response implement Clone
+/// #[message(Token)]
+/// #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// struct Consume(Token);
+///
+/// #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// struct Token(u64);
+/// # impl Handler<Consume> for Sink {
+/// #     async fn handle_message<D: Delegate>(&self, message: Consume, _context: &ActorContext<D>) -> Token { message.0 }
+/// # }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let system = Fluxion::new("system", ());
+/// let local = system.add(Sink).await.unwrap();
+/// assert_eq!(local.send(Consume(Token(1))).await.unwrap().0, 1);
+///
+/// let sender = system.get::<Sink, Consume>(Identifier::Local(local.get_id())).await.unwrap();
+/// assert_eq!(sender.send(Consume(Token(2))).await.unwrap().0, 2);
+/// # });
+/// ```
 #[async_trait::async_trait]
 pub trait MessageSender<M: Message>: Send + Sync + 'static {
 
@@ -27,11 +56,186 @@ pub trait MessageSender<M: Message>: Send + Sync + 'static {
     /// For [`LocalRef`], the message send will never fail, however delegates may return an error upon sending.
     /// These errors are generally not recoverable, and should be interpreted as meaning that the
     /// target actor no longer exists/is no longer accessible.
+    /// In particular, [`MessageSendError::ActorNotFound`] indicates that the sender's transport has closed,
+    /// and that the actor should be re-resolved if it is still needed.
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "foreign", feature = "serde"))] {
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, Identifier, IndeterminateMessage, Message, MessageSendError, MessageSender};
+    /// # use serde::{Deserialize, Serialize};
+    /// # use std::sync::{Arc, Mutex};
+    /// # use tokio::{sync::{mpsc, oneshot}, task::JoinHandle};
+    /// # #[actor] struct Doubler;
+    /// # #[message(u64)] #[derive(Serialize, Deserialize)] struct Double(u64);
+    /// # impl Handler<Double> for Doubler {
+    /// #     async fn handle_message<D: Delegate>(&self, message: Double, _context: &ActorContext<D>) -> u64 { message.0 * 2 }
+    /// # }
+    /// /// Sends messages over a channel to a task, which handles them on another system
+    /// struct ChannelSender<M: Message>(mpsc::Sender<(M, oneshot::Sender<M::Result>)>);
+    ///
+    /// #[async_trait::async_trait]
+    /// impl<M: Message> MessageSender<M> for ChannelSender<M> {
+    ///     async fn send(&self, message: M) -> Result<M::Result, MessageSendError> {
+    ///         let (respond, response) = oneshot::channel();
+    ///
+    ///         // If either half of the exchange has closed, the task is gone
+    ///         self.0.send((message, respond)).await.map_err(|_| MessageSendError::ActorNotFound)?;
+    ///         response.await.map_err(|_| MessageSendError::ActorNotFound)
+    ///     }
+    /// }
+    ///
+    /// /// Resolves foreign actors by starting a task that forwards messages to them
+    /// struct ChannelDelegate {
+    ///     remote: Fluxion,
+    ///     tasks: Mutex<Vec<JoinHandle<()>>>,
+    /// }
+    ///
+    /// impl Delegate for ChannelDelegate {
+    ///     async fn get_actor<A: Handler<M>, M: IndeterminateMessage>(&self, id: Identifier<'_>) -> Option<Arc<dyn MessageSender<M>>>
+    ///         where M::Result: Serialize + for<'a> Deserialize<'a> {
+    ///         let Identifier::Foreign(id, _) = id else { return None };
+    ///         let actor = self.remote.get_local::<A>(id).await.ok()?;
+    ///
+    ///         let (sender, mut receiver) = mpsc::channel::<(M, oneshot::Sender<M::Result>)>(8);
+    ///         let task = tokio::spawn(async move {
+    ///             while let Some((message, respond)) = receiver.recv().await {
+    ///                 let _ = respond.send(actor.send(message).await.unwrap());
+    ///             }
+    ///         });
+    ///         self.tasks.lock().unwrap().push(task);
+    ///
+    ///         Some(Arc::new(ChannelSender(sender)))
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let remote = Fluxion::new("remote", ());
+    /// let id = remote.add_id(Doubler).await.unwrap();
+    ///
+    /// let system = Fluxion::new("local", ChannelDelegate { remote, tasks: Mutex::default() });
+    /// let doubler = system.get::<Doubler, Double>(Identifier::Foreign(id, "remote")).await.unwrap();
+    /// assert_eq!(doubler.send(Double(21)).await.unwrap(), 42);
+    ///
+    /// // Stop the task while the sender is still held, closing its channel
+    /// let tasks = std::mem::take(&mut *system.get_delegate().tasks.lock().unwrap());
+    /// for task in tasks {
+    ///     task.abort();
+    ///     let _ = task.await;
+    /// }
+    ///
+    /// let res = doubler.send(Double(21)).await;
+    /// assert!(matches!(res, Err(MessageSendError::ActorNotFound)));
+    /// # });
+    /// # }
+    /// ```
     async fn send(&self, message: M) -> Result<M::Result, MessageSendError>;
+
+    /// Sends the given message and waits for a response, giving up once `timeout` has elapsed.
+    /// The timeout is measured using the sender's [`MessageSender::timer`], which for senders retrieved from a system
+    /// is the one set with [`Fluxion::with_timer`]. If the timeout elapses first, the send is cancelled by dropping it, which may leave a local handler
+    /// partially complete, or a foreign system still processing the message.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, MessageSendError, MessageSender, Timer};
+    /// # use std::{future::Future, pin::Pin, time::Duration};
+    /// # struct TokioTimer;
+    /// # impl Timer for TokioTimer {
+    /// #     fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+    /// #         Box::pin(tokio::time::sleep(duration))
+    /// #     }
+    /// # }
+    /// # #[actor] struct Sleeper;
+    /// # #[message] struct Sleep(Duration);
+    /// # impl Handler<Sleep> for Sleeper {
+    /// #     async fn handle_message<D: Delegate>(&self, message: Sleep, _context: &ActorContext<D>) { tokio::time::sleep(message.0).await }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ()).with_timer(TokioTimer);
+    /// let actor = system.add(Sleeper).await.unwrap();
+    ///
+    /// assert!(actor.request_timeout(Sleep(Duration::ZERO), Duration::from_secs(1)).await.is_ok());
+    ///
+    /// let res = actor.request_timeout(Sleep(Duration::from_secs(1)), Duration::from_millis(10)).await;
+    /// assert!(matches!(res, Err(MessageSendError::Timeout)));
+    ///
+    /// // Without a timer, the timeout can't be measured
+    /// let untimed = Fluxion::new("untimed", ()).add(Sleeper).await.unwrap();
+    /// let res = untimed.request_timeout(Sleep(Duration::ZERO), Duration::from_secs(1)).await;
+    /// assert!(matches!(res, Err(MessageSendError::NoTimer)));
+    /// # });
+    /// ```
+    ///
+    /// Senders for foreign actors also carry the system's timer, whichever sender the delegate returns.
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "foreign", feature = "serde"))] {
+    /// # use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, Identifier, IndeterminateMessage, MessageSendError, MessageSender, Timer};
+    /// # use serde::{Deserialize, Serialize};
+    /// # use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+    /// # struct TokioTimer;
+    /// # impl Timer for TokioTimer {
+    /// #     fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+    /// #         Box::pin(tokio::time::sleep(duration))
+    /// #     }
+    /// # }
+    /// # #[actor] struct Sleeper;
+    /// # #[message] #[derive(Serialize, Deserialize)] struct Sleep(Duration);
+    /// # impl Handler<Sleep> for Sleeper {
+    /// #     async fn handle_message<D: Delegate>(&self, message: Sleep, _context: &ActorContext<D>) { tokio::time::sleep(message.0).await }
+    /// # }
+    /// /// Resolves foreign actors on a remote system that has no timer
+    /// struct RemoteDelegate(Fluxion);
+    ///
+    /// impl Delegate for RemoteDelegate {
+    ///     async fn get_actor<A: Handler<M>, M: IndeterminateMessage>(&self, id: Identifier<'_>) -> Option<Arc<dyn MessageSender<M>>>
+    ///         where M::Result: Serialize + for<'a> Deserialize<'a> {
+    ///         let Identifier::Foreign(id, _) = id else { return None };
+    ///         Some(Arc::new(self.0.get_local::<A>(id).await.ok()?))
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let remote = Fluxion::new("remote", ());
+    /// let id = remote.add_id(Sleeper).await.unwrap();
+    ///
+    /// let system = Fluxion::new("local", RemoteDelegate(remote)).with_timer(TokioTimer);
+    /// let sleeper = system.get::<Sleeper, Sleep>(Identifier::Foreign(id, "remote")).await.unwrap();
+    ///
+    /// let res = sleeper.request_timeout(Sleep(Duration::from_secs(1)), Duration::from_millis(10)).await;
+    /// assert!(matches!(res, Err(MessageSendError::Timeout)));
+    /// # });
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`MessageSendError::Timeout`] if the timeout elapses before a response is received,
+    /// [`MessageSendError::NoTimer`] without sending the message if the sender has no timer,
+    /// or any error returned by [`MessageSender::send`].
+    async fn request_timeout(&self, message: M, timeout: core::time::Duration) -> Result<M::Result, MessageSendError> {
+        let timer = self.timer().ok_or(MessageSendError::NoTimer)?;
+
+        with_deadline(self.send(message), timer.sleep(timeout)).await
+            .unwrap_or(Err(MessageSendError::Timeout))
+    }
+
+    /// Returns the [`Timer`] used by [`MessageSender::request_timeout`], if any.
+    /// By default, senders have no timer. Senders retrieved from a system with a timer return the system's timer.
+    fn timer(&self) -> Option<&dyn Timer> {
+        None
+    }
 }
 
 
-pub struct LocalRef<A: Actor, D: Delegate>(pub(crate) slacktor::ActorHandle<ActorWrapper<A, D>>, pub(crate) u64);
+pub struct LocalRef<A: Actor, D: Delegate = ()>(
+    pub(crate) slacktor::ActorHandle<ActorWrapper<A, D>>,
+    pub(crate) u64,
+    /// Limits the number of messages in flight to the actor, if it has a [`Actor::MAILBOX_CAPACITY`]
+    pub(crate) Option<Arc<Semaphore>>,
+    /// Notified when a send fails, if the system has [`Metrics`] installed
+    pub(crate) Option<Arc<dyn Metrics>>,
+    /// Measures the timeouts of [`MessageSender::request_timeout`], if the system has a [`Timer`]
+    pub(crate) Option<Arc<dyn Timer>>,
+);
 
 impl<A: Actor, D: Delegate> LocalRef<A, D> {
     /// # [`LocalRef::get_id`]
@@ -42,9 +246,91 @@ impl<A: Actor, D: Delegate> LocalRef<A, D> {
     }
 }
 
+impl<A: Actor, D: Delegate> LocalRef<A, D> {
+    /// # [`LocalRef::any_sender`]
+    /// Starts building an [`AnySender`] for this actor, which can send messages without knowing their types.
+    /// Each message type it should be able to send must be registered with [`AnySenderBuilder::with_message`].
+    #[must_use]
+    pub fn any_sender(&self) -> AnySenderBuilder<A, D> {
+        AnySenderBuilder {
+            actor: self.clone(),
+            senders: BTreeMap::new(),
+        }
+    }
+}
+
 impl<A: Actor, D: Delegate> Clone for LocalRef<A, D> {
     fn clone(&self) -> Self {
-        Self(self.0.clone(), self.1)
+        Self(self.0.clone(), self.1, self.2.clone(), self.3.clone(), self.4.clone())
+    }
+}
+
+impl<A: Actor, D: Delegate> LocalRef<A, D> {
+    /// # [`LocalRef::try_send`]
+    /// Sends the given message and waits for a response, unless the actor already has as many messages
+    /// in flight as its [`Actor::MAILBOX_CAPACITY`] allows. Unlike [`MessageSender::send`], this never waits for capacity.
+    ///
+    /// ```
+    /// # use fluxion::{message, Actor, ActorContext, Delegate, Fluxion, Handler, MessageSendError, MessageSender};
+    /// # use std::time::Duration;
+    /// struct Slow;
+    ///
+    /// impl Actor for Slow {
+    ///     type Error = ();
+    ///     const MAILBOX_CAPACITY: Option<usize> = Some(1);
+    /// }
+    ///
+    /// #[message]
+    /// struct Work;
+    ///
+    /// impl Handler<Work> for Slow {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Work, _context: &ActorContext<D>) {
+    ///         tokio::time::sleep(Duration::from_millis(100)).await;
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let system = Fluxion::new("system", ());
+    /// let slow = system.add(Slow).await.unwrap();
+    ///
+    /// // Occupy the actor's only slot
+    /// let busy = tokio::spawn({
+    ///     let slow = slow.clone();
+    ///     async move { slow.send(Work).await }
+    /// });
+    /// tokio::time::sleep(Duration::from_millis(10)).await;
+    ///
+    /// assert!(matches!(slow.try_send(Work).await, Err(MessageSendError::MailboxFull)));
+    ///
+    /// // Waits for the slot to free up instead
+    /// slow.send(Work).await.unwrap();
+    /// # busy.await.unwrap().unwrap();
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`MessageSendError::MailboxFull`] if the actor is at capacity,
+    /// or [`MessageSendError::Validation`] if the message is rejected by [`Handler::validate`].
+    pub async fn try_send<M: Message>(&self, message: M) -> Result<M::Result, MessageSendError>
+        where A: Handler<M> {
+        A::validate(&message).map_err(|e| self.send_error::<M>(MessageSendError::Validation(e)))?;
+
+        // Take a slot without waiting, if the actor has a limit
+        let _permit = self.2.as_ref()
+            .map(|inflight| inflight.try_acquire(1))
+            .transpose()
+            .map_err(|_| self.send_error::<M>(MessageSendError::MailboxFull))?;
+
+        Ok(self.0.send(message).await)
+    }
+
+    /// Reports a failed send of a message of type `M` to the system's metrics, if any, and returns the error
+    pub(crate) fn send_error<M: Message>(&self, error: MessageSendError) -> MessageSendError {
+        if let Some(metrics) = &self.3 {
+            metrics.on_send_error(self.1, core::any::type_name::<M>(), &error);
+        }
+
+        error
     }
 }
 
@@ -53,6 +339,280 @@ impl<A: Handler<M>, M: Message, D: Delegate> MessageSender<M> for LocalRef<A, D>
 
     #[inline]
     async fn send(&self, message: M) -> Result<M::Result, MessageSendError> {
+        A::validate(&message).map_err(|e| self.send_error::<M>(MessageSendError::Validation(e)))?;
+
+        // Wait for capacity, if the actor has a limit.
+        // The semaphore is never closed, so this can't fail.
+        let _permit = match &self.2 {
+            Some(inflight) => inflight.acquire(1).await.ok(),
+            None => None,
+        };
+
         Ok(self.0.send(message).await)
     }
+
+    fn timer(&self) -> Option<&dyn Timer> {
+        self.4.as_deref()
+    }
+}
+
+
+/// A [`MessageSender`] returned by a [`Delegate`], which carries the system's [`Timer`]
+/// so that [`MessageSender::request_timeout`] can be used with foreign actors.
+#[cfg(feature = "foreign")]
+pub(crate) struct TimedSender<M: Message> {
+    /// The sender returned by the delegate
+    pub(crate) sender: Arc<dyn MessageSender<M>>,
+    /// The system's timer
+    pub(crate) timer: Arc<dyn Timer>,
+}
+
+#[cfg(feature = "foreign")]
+#[async_trait::async_trait]
+impl<M: Message> MessageSender<M> for TimedSender<M> {
+    async fn send(&self, message: M) -> Result<M::Result, MessageSendError> {
+        self.sender.send(message).await
+    }
+
+    fn timer(&self) -> Option<&dyn Timer> {
+        Some(self.timer.as_ref())
+    }
+}
+
+
+/// # [`AnyLocalRef`]
+/// A type-erased reference to a local actor, retrieved with [`Fluxion::get_local_any`].
+/// This can be used to manage an actor whose type is not known, but not to send it messages.
+///
+/// <div class = "warn">
+/// Unless the `deterministic-ids` feature is enabled, ids are reused after an actor is killed.
+/// If this reference's actor is killed and another actor of the same type is assigned its id,
+/// this reference will refer to the new actor.
+/// </div>
+pub struct AnyLocalRef<D: Delegate = ()> {
+    /// The system the actor resides on
+    pub(crate) system: Fluxion<D>,
+    /// The actor's id
+    pub(crate) id: u64,
+    /// The actor's type name
+    pub(crate) type_name: &'static str,
+    /// The actor's type id, which is compared to tell whether the actor is still alive
+    pub(crate) type_id: TypeId,
+}
+
+impl<D: Delegate> AnyLocalRef<D> {
+    /// # [`AnyLocalRef::get_id`]
+    /// Retrieves the actor's ID
+    #[must_use]
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    /// # [`AnyLocalRef::type_name`]
+    /// Retrieves the actor's type name, as returned by [`core::any::type_name`]
+    #[must_use]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// # [`AnyLocalRef::is_alive`]
+    /// Returns true if the actor has not been killed
+    pub async fn is_alive(&self) -> bool {
+        self.system.actor_type_id(self.id).await == Some(self.type_id)
+    }
+
+    /// # [`AnyLocalRef::kill`]
+    /// Kills the actor. Does nothing if the actor has already been killed.
+    pub async fn kill(&self) {
+        self.system.kill_any(self.id, self.type_id).await;
+    }
+}
+
+impl<D: Delegate> Clone for AnyLocalRef<D> {
+    fn clone(&self) -> Self {
+        Self {
+            system: self.system.clone(),
+            id: self.id,
+            type_name: self.type_name,
+            type_id: self.type_id,
+        }
+    }
+}
+
+
+/// Sends a type-erased message of one type registered on an [`AnySender`], returning its type-erased response
+type AnySendFn = Box<dyn Fn(Box<dyn Any + Send>) -> Pin<Box<dyn Future<Output = Result<Box<dyn Any + Send>, MessageSendError>> + Send>> + Send + Sync>;
+
+/// # [`AnySender`]
+/// A type-erased reference to a local actor which can send any of the message types it was built with,
+/// without the caller knowing either the actor's type or the message's type. This allows references
+/// to different kinds of actors to be stored together, such as by a generic router.
+/// Created with [`LocalRef::any_sender`].
+///
+/// ```
+/// # use fluxion::{actor, message, ActorContext, AnySender, Delegate, Fluxion, Handler, MessageSendError};
+/// # use std::any::Any;
+/// # #[actor] struct Echo;
+/// # #[actor] struct Counter;
+/// # #[message(String)] struct Say(&'static str);
+/// # #[message(u64)] struct Count;
+/// # impl Handler<Say> for Echo {
+/// #     async fn handle_message<D: Delegate>(&self, message: Say, _context: &ActorContext<D>) -> String { message.0.to_string() }
+/// # }
+/// # impl Handler<Count> for Counter {
+/// #     async fn handle_message<D: Delegate>(&self, _message: Count, _context: &ActorContext<D>) -> u64 { 1 }
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let system = Fluxion::new("system", ());
+/// let echo = system.add(Echo).await.unwrap();
+/// let counter = system.add(Counter).await.unwrap();
+///
+/// let routes: Vec<AnySender> = vec![
+///     echo.any_sender().with_message::<Say>().build(),
+///     counter.any_sender().with_message::<Count>().build(),
+/// ];
+///
+/// let response = routes[0].send_any(Box::new(Say("hello"))).await.unwrap();
+/// assert_eq!(*response.downcast::<String>().unwrap(), "hello");
+///
+/// let response = routes[1].send_any(Box::new(Count)).await.unwrap();
+/// assert_eq!(*response.downcast::<u64>().unwrap(), 1);
+///
+/// let res = routes[1].send_any(Box::new(Say("hello"))).await;
+/// assert!(matches!(res, Err(MessageSendError::Unhandled)));
+/// # });
+/// ```
+pub struct AnySender {
+    /// The actor's id
+    id: u64,
+    /// Sends each registered message type, keyed by the message's type
+    senders: Arc<BTreeMap<TypeId, AnySendFn>>,
+}
+
+impl AnySender {
+    /// # [`AnySender::get_id`]
+    /// Retrieves the actor's ID
+    #[must_use]
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    /// # [`AnySender::handles`]
+    /// Returns true if this sender was built with the message type with the given [`TypeId`].
+    #[must_use]
+    pub fn handles(&self, message: TypeId) -> bool {
+        self.senders.contains_key(&message)
+    }
+
+    /// # [`AnySender::send_any`]
+    /// Sends the given message, which must be of one of the types this sender was built with,
+    /// and waits for the actor's response.
+    ///
+    /// # Errors
+    /// Returns [`MessageSendError::Unhandled`] if this sender was not built with the message's type,
+    /// or any error returned by [`MessageSender::send`].
+    pub async fn send_any(&self, message: Box<dyn Any + Send>) -> Result<Box<dyn Any + Send>, MessageSendError> {
+        let Some(send) = self.senders.get(&(*message).type_id()) else {
+            return Err(MessageSendError::Unhandled);
+        };
+
+        send(message).await
+    }
+}
+
+impl Clone for AnySender {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            senders: self.senders.clone(),
+        }
+    }
+}
+
+/// # [`AnySenderBuilder`]
+/// Builds an [`AnySender`] for a local actor, registering the message types it will be able to send.
+/// Created with [`LocalRef::any_sender`].
+pub struct AnySenderBuilder<A: Actor, D: Delegate = ()> {
+    /// The actor to send messages to
+    actor: LocalRef<A, D>,
+    /// The message types registered so far
+    senders: BTreeMap<TypeId, AnySendFn>,
+}
+
+impl<A: Actor, D: Delegate> AnySenderBuilder<A, D> {
+    /// # [`AnySenderBuilder::with_message`]
+    /// Allows the built [`AnySender`] to send messages of type `M`.
+    #[must_use]
+    pub fn with_message<M: Message>(mut self) -> Self
+        where A: Handler<M> {
+        let actor = self.actor.clone();
+
+        self.senders.insert(TypeId::of::<M>(), Box::new(move |message| {
+            let actor = actor.clone();
+
+            Box::pin(async move {
+                // Senders are keyed by the message's type, so this can't fail
+                let message = message.downcast::<M>().map_err(|_| MessageSendError::Unhandled)?;
+
+                actor.send(*message).await
+                    .map(|response| Box::new(response) as Box<dyn Any + Send>)
+            })
+        }));
+
+        self
+    }
+
+    /// # [`AnySenderBuilder::build`]
+    /// Creates the [`AnySender`].
+    #[must_use]
+    pub fn build(self) -> AnySender {
+        AnySender {
+            id: self.actor.get_id(),
+            senders: Arc::new(self.senders),
+        }
+    }
+}
+
+
+/// # [`Pipe`]
+/// Connects two local actors in a pipeline, retrieved with [`Fluxion::pipe`].
+/// Each message sent through the pipe is handled by the first actor, and its response is transformed
+/// into a message for the second actor. The second actor's response is returned to the caller.
+///
+/// <div class = "info">
+/// The pipe does not buffer messages. Each send waits for both actors to handle the message,
+/// so a slow second actor will also slow down every send through the pipe.
+/// Messages sent directly to the first actor are not forwarded to the second.
+/// </div>
+pub struct Pipe<A: Handler<M>, B: Handler<N>, M: Message, N: Message, D: Delegate = ()> {
+    /// The first actor in the pipeline
+    pub(crate) first: LocalRef<A, D>,
+    /// The second actor in the pipeline
+    pub(crate) second: LocalRef<B, D>,
+    /// Transforms the first actor's response into the second actor's message
+    pub(crate) transform: fn(M::Result) -> N,
+}
+
+impl<A: Handler<M>, B: Handler<N>, M: Message, N: Message, D: Delegate> Clone for Pipe<A, B, M, N, D> {
+    fn clone(&self) -> Self {
+        Self {
+            first: self.first.clone(),
+            second: self.second.clone(),
+            transform: self.transform,
+        }
+    }
+}
+
+impl<A: Handler<M>, B: Handler<N>, M: Message, N: Message, D: Delegate> Pipe<A, B, M, N, D> {
+    /// # [`Pipe::send`]
+    /// Sends a message to the first actor, and sends its transformed response to the second actor,
+    /// returning the second actor's response.
+    ///
+    /// # Errors
+    /// Returns an error if either send fails.
+    pub async fn send(&self, message: M) -> Result<N::Result, MessageSendError> {
+        let response = self.first.send(message).await?;
+
+        self.second.send((self.transform)(response)).await
+    }
 }
@@ -0,0 +1,130 @@
+//! # Scheduling
+//! Fluxion is executor-agnostic, so it can not run background tasks by itself.
+//! Functionality that does, such as [`ActorContext::schedule`](crate::ActorContext::schedule),
+//! instead uses a [`Spawner`] provided by the application, along with its [`Timer`](crate::Timer).
+
+use alloc::{boxed::Box, sync::Arc};
+use core::{future::Future, pin::Pin, sync::atomic::{AtomicBool, Ordering}, task::Poll};
+use maitake_sync::WaitQueue;
+
+
+/// # [`Spawner`]
+/// Provides Fluxion with the ability to run futures in the background.
+/// This is usually implemented by wrapping the spawn function of the executor in use.
+///
+/// ```
+/// # use fluxion::Spawner;
+/// # use std::{future::Future, pin::Pin};
+/// struct TokioSpawner;
+///
+/// impl Spawner for TokioSpawner {
+///     fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>) {
+///         tokio::spawn(future);
+///     }
+/// }
+/// ```
+pub trait Spawner: Send + Sync + 'static {
+    /// # [`Spawner::spawn`]
+    /// Runs the given future to completion in the background.
+    fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>);
+}
+
+/// Shared between a [`ScheduleHandle`] and its task, allowing the task to be cancelled
+pub(crate) struct Cancellation {
+    /// Set once the task has been cancelled
+    cancelled: AtomicBool,
+    /// Woken when the task is cancelled
+    waiters: WaitQueue,
+}
+
+impl Cancellation {
+    /// Cancels the task
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+        self.waiters.wake_all();
+    }
+
+    /// Returns true if the task has been cancelled.
+    /// Tasks check this before sending, so that nothing is sent once the handle has been dropped.
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Waits until the task is cancelled
+    async fn cancelled(&self) {
+        loop {
+            let wait = self.waiters.wait();
+
+            if self.cancelled.load(Ordering::Acquire) {
+                return;
+            }
+
+            // The queue is never closed, so this can't fail
+            let _ = wait.await;
+        }
+    }
+}
+
+/// # [`ScheduleHandle`]
+/// A handle to a task scheduled with [`ActorContext::schedule`](crate::ActorContext::schedule)
+/// or [`ActorContext::schedule_interval`](crate::ActorContext::schedule_interval).
+/// Dropping the handle cancels the task, unless it is detached with [`ScheduleHandle::detach`].
+#[must_use = "dropping a `ScheduleHandle` cancels its task"]
+pub struct ScheduleHandle {
+    /// Cancels the task on drop, or [`None`] if the task has been detached
+    cancellation: Option<Arc<Cancellation>>,
+}
+
+impl ScheduleHandle {
+    /// Spawns the task returned by `task`, returning a handle that cancels it when dropped.
+    /// The task is given its [`Cancellation`], so that it can check whether it has been cancelled before doing anything.
+    pub(crate) fn spawn<F: Future<Output = ()> + Send + 'static>(spawner: &dyn Spawner, task: impl FnOnce(Arc<Cancellation>) -> F) -> Self {
+        let cancellation = Arc::new(Cancellation {
+            cancelled: AtomicBool::new(false),
+            waiters: WaitQueue::new(),
+        });
+
+        let task = task(cancellation.clone());
+        let task_cancellation = cancellation.clone();
+        spawner.spawn(Box::pin(async move {
+            until_cancelled(task, task_cancellation.cancelled()).await;
+        }));
+
+        Self { cancellation: Some(cancellation) }
+    }
+
+    /// # [`ScheduleHandle::cancel`]
+    /// Cancels the task. This is equivalent to dropping the handle.
+    pub fn cancel(self) {}
+
+    /// # [`ScheduleHandle::detach`]
+    /// Lets the task run to completion without keeping the handle around.
+    /// The task can no longer be cancelled.
+    pub fn detach(mut self) {
+        self.cancellation = None;
+    }
+}
+
+impl Drop for ScheduleHandle {
+    fn drop(&mut self) {
+        if let Some(cancellation) = &self.cancellation {
+            cancellation.cancel();
+        }
+    }
+}
+
+/// Polls `future` until it completes, unless `cancelled` completes first.
+/// Unlike [`with_deadline`](crate::timer::with_deadline), the cancellation is polled first,
+/// so the future is never polled again once it has been cancelled.
+async fn until_cancelled(future: impl Future<Output = ()>, cancelled: impl Future<Output = ()>) {
+    let mut future = core::pin::pin!(future);
+    let mut cancelled = core::pin::pin!(cancelled);
+
+    core::future::poll_fn(|cx| {
+        if cancelled.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(());
+        }
+
+        future.as_mut().poll(cx)
+    }).await;
+}
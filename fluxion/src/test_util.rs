@@ -0,0 +1,481 @@
+//! # Test Utilities
+//! Utilities for testing and benchmarking Fluxion and applications built on it.
+//! This module is only available with the `test-util` feature, which requires `std`.
+
+extern crate std;
+
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::time::Duration;
+use std::{sync::Mutex, time::Instant};
+
+use crate::{ActorContext, AddError, Delegate, Fluxion, Handler, LocalRef, Message, MessageSendError, MessageSender, Timer};
+
+
+/// # [`TestHarness`]
+/// Calls an actor's [`Handler::handle_message`] directly, without adding the actor to a system.
+/// The handler is given a context for an actor with the id [`TestHarness::ID`] on the harness's system.
+/// Any actors that the handler sends messages to can be added to [`TestHarness::system`] beforehand,
+/// and their state inspected afterwards to check what the handler sent.
+///
+/// Actors can also be replaced by stubs registered with [`TestHarness::stub`], which records every message sent to them.
+/// The recorded sends can then be checked with [`TestHarness::sent`] and [`TestHarness::assert_sent`].
+///
+/// Messages passed to [`TestHarness::handle`] are not validated with [`Handler::validate`].
+/// As the handled actor is not on the system, [`ActorContext::add_child`] fails with [`SystemError::NotFound`](crate::SystemError::NotFound).
+///
+/// ```
+/// # use fluxion::{actor, message, test_util::TestHarness, ActorContext, Delegate, Handler, MessageSender};
+/// # use std::sync::atomic::{AtomicU64, Ordering};
+/// #[actor]
+/// struct Counter(AtomicU64);
+///
+/// #[message]
+/// struct Add(u64);
+///
+/// #[message(u64)]
+/// struct Total;
+///
+/// impl Handler<Add> for Counter {
+///     async fn handle_message<D: Delegate>(&self, message: Add, _context: &ActorContext<D>) {
+///         self.0.fetch_add(message.0, Ordering::Relaxed);
+///     }
+/// }
+///
+/// impl Handler<Total> for Counter {
+///     async fn handle_message<D: Delegate>(&self, _message: Total, _context: &ActorContext<D>) -> u64 {
+///         self.0.load(Ordering::Relaxed)
+///     }
+/// }
+///
+/// #[actor]
+/// struct Doubler { counter: u64 }
+///
+/// impl Handler<Add> for Doubler {
+///     async fn handle_message<D: Delegate>(&self, message: Add, context: &ActorContext<D>) {
+///         let counter = context.system().get_local::<Counter>(self.counter).await.unwrap();
+///         counter.send(Add(message.0 * 2)).await.unwrap();
+///     }
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let harness = TestHarness::new();
+/// let counter = harness.system().add(Counter(AtomicU64::new(0))).await.unwrap();
+///
+/// harness.handle(&Doubler { counter: counter.get_id() }, Add(21)).await;
+///
+/// assert_eq!(counter.send(Total).await.unwrap(), 42);
+/// # });
+/// ```
+pub struct TestHarness<D = ()> {
+    /// The context passed to handlers
+    context: ActorContext<D>,
+    /// The messages sent to stubs since the last call to [`TestHarness::handle`]
+    sent: Arc<Mutex<Vec<Sent>>>,
+}
+
+impl TestHarness {
+    /// # [`TestHarness::new`]
+    /// Creates a harness on a new, empty system with no delegate.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_system(Fluxion::new("test", ()))
+    }
+}
+
+impl Default for TestHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: Delegate> TestHarness<D> {
+    /// # [`TestHarness::ID`]
+    /// The id of the actor that handlers are run as. Actors added to the system will never be assigned this id.
+    pub const ID: u64 = u64::MAX;
+
+    /// # [`TestHarness::with_system`]
+    /// Creates a harness that runs handlers on the given system.
+    #[must_use]
+    pub fn with_system(system: Fluxion<D>) -> Self {
+        Self {
+            context: ActorContext {
+                system,
+                id: Self::ID,
+                parent: None,
+                #[cfg(feature = "metrics")]
+                counters: Arc::default(),
+            },
+            sent: Arc::default(),
+        }
+    }
+
+    /// # [`TestHarness::system`]
+    /// Returns the system that handlers are run on.
+    #[must_use]
+    pub fn system(&self) -> &Fluxion<D> {
+        &self.context.system
+    }
+
+    /// # [`TestHarness::context`]
+    /// Returns the context that is passed to handlers.
+    #[must_use]
+    pub fn context(&self) -> &ActorContext<D> {
+        &self.context
+    }
+
+    /// # [`TestHarness::handle`]
+    /// Handles the given message with the given actor, returning the handler's response.
+    /// Sends recorded by earlier calls are cleared first.
+    ///
+    /// # Panics
+    /// Panics if the record of sends has been poisoned by a panicking stub.
+    pub async fn handle<A: Handler<M>, M: Message>(&self, actor: &A, message: M) -> M::Result {
+        self.sent.lock().unwrap().clear();
+        actor.handle_message(message, &self.context).await
+    }
+
+    /// # [`TestHarness::stub`]
+    /// Registers a stub that stands in for the local actor with the given id whenever it is retrieved by id for messages of type `M`
+    /// with [`Fluxion::get`]. Messages sent to the stub are recorded, and then passed on to it.
+    /// Stubs take precedence over actors on the system with the same id, so `id` should be one that the system will not assign.
+    ///
+    /// ```
+    /// # use fluxion::{actor, message, test_util::{Sent, TestHarness}, ActorContext, Delegate, Fluxion, Handler, MessageSender};
+    /// # use std::sync::Arc;
+    /// #[actor]
+    /// struct Notifier { mailer: u64 }
+    ///
+    /// #[message(bool)]
+    /// #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    /// struct Email(u64);
+    ///
+    /// #[message]
+    /// struct Signup(u64);
+    ///
+    /// impl Handler<Signup> for Notifier {
+    ///     async fn handle_message<D: Delegate>(&self, message: Signup, context: &ActorContext<D>) {
+    ///         let mailer = context.system().get::<Mailer, Email>(self.mailer).await.unwrap();
+    ///         assert!(mailer.send(Email(message.0)).await.unwrap());
+    ///     }
+    /// }
+    ///
+    /// // The stub is an actor on a separate system, which accepts every email
+    /// #[actor]
+    /// struct Mailer;
+    ///
+    /// impl Handler<Email> for Mailer {
+    ///     async fn handle_message<D: Delegate>(&self, _message: Email, _context: &ActorContext<D>) -> bool { true }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let harness = TestHarness::new();
+    /// let mailer = Fluxion::new("stubs", ()).add(Mailer).await.unwrap();
+    /// harness.stub::<Email>(7, Arc::new(mailer));
+    ///
+    /// harness.handle(&Notifier { mailer: 7 }, Signup(42)).await;
+    ///
+    /// harness.assert_sent::<Email>(7);
+    /// assert_eq!(harness.sent(), [Sent { to: 7, message: core::any::type_name::<Email>() }]);
+    /// # });
+    /// ```
+    pub fn stub<M: Message>(&self, id: u64, stub: Arc<dyn MessageSender<M>>) {
+        self.context.system.insert_stub(id, Arc::new(Recorder {
+            to: id,
+            stub,
+            sent: self.sent.clone(),
+        }));
+    }
+
+    /// # [`TestHarness::sent`]
+    /// Returns the messages sent to stubs since the last call to [`TestHarness::handle`], in the order they were sent.
+    ///
+    /// # Panics
+    /// Panics if the record of sends has been poisoned by a panicking stub.
+    #[must_use]
+    pub fn sent(&self) -> Vec<Sent> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    /// # [`TestHarness::assert_sent`]
+    /// Panics unless a message of type `M` was sent to the stub with the given id since the last call to [`TestHarness::handle`].
+    ///
+    /// # Panics
+    /// See above.
+    pub fn assert_sent<M: Message>(&self, to: u64) {
+        let expected = Sent { to, message: core::any::type_name::<M>() };
+        let sent = self.sent();
+        assert!(sent.contains(&expected), "expected {} to be sent to {to}, but the handler sent {sent:?}", expected.message);
+    }
+}
+
+
+/// # [`Sent`]
+/// A message sent to a stub registered with [`TestHarness::stub`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sent {
+    /// The id of the stub that the message was sent to
+    pub to: u64,
+    /// The message's type name, as returned by [`core::any::type_name`]
+    pub message: &'static str,
+}
+
+/// Records every message sent through it, before passing the message on to the stub it wraps
+struct Recorder<M: Message> {
+    /// The id of the stub
+    to: u64,
+    /// The stub that messages are passed on to
+    stub: Arc<dyn MessageSender<M>>,
+    /// The record of sends shared with the harness
+    sent: Arc<Mutex<Vec<Sent>>>,
+}
+
+#[async_trait::async_trait]
+impl<M: Message> MessageSender<M> for Recorder<M> {
+    async fn send(&self, message: M) -> Result<M::Result, MessageSendError> {
+        self.sent.lock().unwrap().push(Sent { to: self.to, message: core::any::type_name::<M>() });
+        self.stub.send(message).await
+    }
+
+    fn timer(&self) -> Option<&dyn Timer> {
+        self.stub.timer()
+    }
+}
+
+
+/// # [`Benchmark`]
+/// A reusable micro-benchmark for the local message send path.
+/// The benchmark spawns a number of actors, warms them up, and then sends a fixed number of messages
+/// spread evenly across them, timing each send individually.
+///
+/// ```
+/// # use fluxion::{actor, message, test_util::Benchmark, ActorContext, Delegate, Fluxion, Handler};
+/// # #[actor] struct Echo;
+/// # #[message(u64)] struct Ping(u64);
+/// # impl Handler<Ping> for Echo {
+/// #     async fn handle_message<D: Delegate>(&self, message: Ping, _context: &ActorContext<D>) -> u64 { message.0 }
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let system = Fluxion::new("benchmark", ());
+///
+/// let report = Benchmark::new(4, 10_000)
+///     .run(&system, |_| Echo, |i| Ping(i as u64))
+///     .await
+///     .unwrap();
+///
+/// report.assert_throughput_at_least(1_000.0);
+/// # });
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Benchmark {
+    /// The number of actors to spawn
+    actors: usize,
+    /// The number of messages to send and time
+    messages: usize,
+    /// The number of untimed messages to send to each actor before timing begins
+    warmup: usize,
+}
+
+impl Benchmark {
+    /// # [`Benchmark::new`]
+    /// Creates a benchmark that sends `messages` messages across `actors` actors.
+    /// By default, 100 untimed warmup messages are sent to each actor first.
+    #[must_use]
+    pub fn new(actors: usize, messages: usize) -> Self {
+        Self {
+            actors: actors.max(1),
+            messages,
+            warmup: 100,
+        }
+    }
+
+    /// # [`Benchmark::warmup`]
+    /// Sets the number of untimed messages sent to each actor before timing begins.
+    #[must_use]
+    pub fn warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// # [`Benchmark::run`]
+    /// Runs the benchmark on the given system. Actors are created by calling `actor` with their index,
+    /// and messages are created by calling `message` with their index. Message `i` is sent to actor `i % actors`.
+    /// The actors are killed once the benchmark completes.
+    ///
+    /// # Errors
+    /// Returns an error if an actor fails to be added, or a message fails to send.
+    pub async fn run<A, M, D>(&self, system: &Fluxion<D>,
+        mut actor: impl FnMut(usize) -> A,
+        mut message: impl FnMut(usize) -> M) -> Result<BenchmarkReport, BenchmarkError<A::Error>>
+        where A: Handler<M>, M: Message, D: Delegate {
+
+        // Spawn the actors
+        let mut refs: Vec<LocalRef<A, D>> = Vec::with_capacity(self.actors);
+        for i in 0..self.actors {
+            refs.push(system.add(actor(i)).await.map_err(BenchmarkError::Add)?);
+        }
+
+        // Warm up each actor
+        for (i, actor) in refs.iter().enumerate() {
+            for j in 0..self.warmup {
+                actor.send(message(i * self.warmup + j)).await.map_err(BenchmarkError::Send)?;
+            }
+        }
+
+        // Send the messages, timing each individually
+        let mut latencies = Vec::with_capacity(self.messages);
+        let start = Instant::now();
+        for i in 0..self.messages {
+            let message = message(i);
+            let sent = Instant::now();
+            refs[i % refs.len()].send(message).await.map_err(BenchmarkError::Send)?;
+            latencies.push(sent.elapsed());
+        }
+        let elapsed = start.elapsed();
+
+        // Clean up the actors
+        for actor in refs {
+            system.kill::<A>(actor.get_id()).await;
+        }
+
+        Ok(BenchmarkReport::new(elapsed, latencies))
+    }
+}
+
+
+/// # [`BenchmarkError`]
+/// An error that occurred while running a [`Benchmark`].
+/// This implements [`Error`](core::error::Error), so it can be propagated from tests with `?`.
+///
+/// ```
+/// # use fluxion::{message, test_util::Benchmark, Actor, ActorContext, Delegate, Fluxion, Handler};
+/// # #[message] struct Ping;
+/// #[derive(Debug)]
+/// struct Unavailable;
+///
+/// struct Flaky;
+///
+/// impl Actor for Flaky {
+///     type Error = Unavailable;
+///
+///     async fn initialize(&mut self) -> Result<(), Unavailable> {
+///         Err(Unavailable)
+///     }
+/// }
+/// # impl Handler<Ping> for Flaky {
+/// #     async fn handle_message<D: Delegate>(&self, _message: Ping, _context: &ActorContext<D>) {}
+/// # }
+///
+/// async fn benchmark(system: &Fluxion) -> Result<(), Box<dyn std::error::Error>> {
+///     Benchmark::new(1, 10).run(system, |_| Flaky, |_| Ping).await?;
+///     Ok(())
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let error = benchmark(&Fluxion::new("benchmark", ())).await.unwrap_err();
+/// assert!(error.to_string().contains("failed to initialize"));
+/// assert!(error.source().is_some());
+/// # });
+/// ```
+#[derive(Debug)]
+pub enum BenchmarkError<E> {
+    /// An actor could not be added
+    Add(AddError<E>),
+    /// A message failed to send
+    Send(MessageSendError),
+}
+
+impl<E: core::fmt::Debug> core::fmt::Display for BenchmarkError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BenchmarkError::Add(e) => write!(f, "BenchmarkError: {e}"),
+            BenchmarkError::Send(e) => write!(f, "BenchmarkError: {e}"),
+        }
+    }
+}
+
+impl<E: core::fmt::Debug + 'static> core::error::Error for BenchmarkError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            BenchmarkError::Add(e) => Some(e),
+            BenchmarkError::Send(e) => Some(e),
+        }
+    }
+}
+
+
+/// # [`BenchmarkReport`]
+/// The results of a [`Benchmark`] run.
+#[derive(Debug, Clone)]
+pub struct BenchmarkReport {
+    /// The total time taken to send every timed message
+    pub elapsed: Duration,
+    /// The latency of each timed message, sorted from fastest to slowest
+    latencies: Vec<Duration>,
+}
+
+impl BenchmarkReport {
+    /// Creates a report from the total elapsed time and each message's latency
+    fn new(elapsed: Duration, mut latencies: Vec<Duration>) -> Self {
+        latencies.sort_unstable();
+
+        Self { elapsed, latencies }
+    }
+
+    /// # [`BenchmarkReport::messages`]
+    /// Returns the number of timed messages.
+    #[must_use]
+    pub fn messages(&self) -> usize {
+        self.latencies.len()
+    }
+
+    /// # [`BenchmarkReport::throughput`]
+    /// Returns the number of messages sent per second.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn throughput(&self) -> f64 {
+        self.messages() as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// # [`BenchmarkReport::percentile`]
+    /// Returns the latency below which the given percentage of messages fall, using the nearest-rank method.
+    /// `percentile` is clamped to between 0 and 100. Returns [`Duration::ZERO`] if no messages were timed.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * self.latencies.len() as f64).ceil() as usize;
+
+        self.latencies[rank.saturating_sub(1).min(self.latencies.len() - 1)]
+    }
+
+    /// # [`BenchmarkReport::assert_throughput_at_least`]
+    /// Panics if fewer than the given number of messages were sent per second.
+    ///
+    /// # Panics
+    /// See above.
+    pub fn assert_throughput_at_least(&self, messages_per_sec: f64) {
+        let throughput = self.throughput();
+        assert!(throughput >= messages_per_sec, "throughput of {throughput:.2} messages/sec is below the threshold of {messages_per_sec:.2}");
+    }
+
+    /// # [`BenchmarkReport::assert_percentile_at_most`]
+    /// Panics if the latency at the given percentile exceeds the given duration.
+    ///
+    /// # Panics
+    /// See above.
+    pub fn assert_percentile_at_most(&self, percentile: f64, max: Duration) {
+        let latency = self.percentile(percentile);
+        assert!(latency <= max, "p{percentile} latency of {latency:?} exceeds the threshold of {max:?}");
+    }
+}
+
+impl core::fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} messages in {:?} ({:.2} messages/sec), p50 {:?}, p90 {:?}, p99 {:?}, max {:?}",
+            self.messages(), self.elapsed, self.throughput(),
+            self.percentile(50.0), self.percentile(90.0), self.percentile(99.0), self.percentile(100.0))
+    }
+}
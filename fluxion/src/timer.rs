@@ -0,0 +1,55 @@
+//! # Timers
+//! Fluxion is executor-agnostic, so it has no timer of its own.
+//! Functionality that depends on time, such as [`MessageSender::request_timeout`](crate::MessageSender::request_timeout),
+//! instead uses a [`Timer`] provided by the application.
+
+use alloc::boxed::Box;
+use core::{future::Future, pin::Pin, task::Poll, time::Duration};
+
+
+/// # [`Timer`]
+/// Provides Fluxion with the ability to wait for a duration of time.
+/// This is usually implemented by wrapping the sleep function of the executor in use.
+///
+/// ```
+/// # use fluxion::Timer;
+/// # use std::{future::Future, pin::Pin, time::Duration};
+/// struct TokioTimer;
+///
+/// impl Timer for TokioTimer {
+///     fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+///         Box::pin(tokio::time::sleep(duration))
+///     }
+/// }
+/// ```
+pub trait Timer: Send + Sync + 'static {
+    /// # [`Timer::sleep`]
+    /// Returns a future that completes once the given duration has elapsed.
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    /// # [`Timer::now`]
+    /// Returns the current time, measured from an arbitrary fixed point, or [`None`] if the timer can't measure it.
+    /// This is used to measure how long things take, such as for [`Metrics`](crate::Metrics), so only differences between values matter.
+    /// By default, this returns [`None`].
+    fn now(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Waits for `future` to complete, returning [`None`] if `deadline` completes first.
+pub(crate) async fn with_deadline<T>(future: impl Future<Output = T>, deadline: impl Future<Output = ()>) -> Option<T> {
+    let mut future = core::pin::pin!(future);
+    let mut deadline = core::pin::pin!(deadline);
+
+    core::future::poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+
+        if deadline.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+
+        Poll::Pending
+    }).await
+}
@@ -0,0 +1,10 @@
+//! # UI tests
+//! Checks that code using Fluxion's macros compiles, or fails to compile with the expected errors.
+
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/generic_message.rs");
+    cases.pass("tests/ui/generic_actor.rs");
+    cases.compile_fail("tests/ui/missing_message_attribute.rs");
+}
@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use fluxion::{actor, message, Actor, ActorContext, Delegate, Fluxion, Handler, MessageSender};
+
+#[derive(Debug)]
+struct CacheError<K>(K);
+
+/// A generic actor with two parameters, a where clause, and an error type referring to a parameter
+#[actor(CacheError<K>)]
+struct Cache<K, V> where K: Ord + Send + Sync + 'static, V: Clone + Send + Sync + 'static {
+    entries: BTreeMap<K, V>,
+}
+
+/// A generic actor with a bound in its parameter list, using the default error type
+#[actor]
+struct Counter<T: Send + Sync + 'static>(T);
+
+#[message(Option<V>)]
+struct Get<K: Send + Sync + 'static, V: Send + Sync + 'static>(K, core::marker::PhantomData<V>);
+
+impl<K, V> Handler<Get<K, V>> for Cache<K, V> where K: Ord + Send + Sync + 'static, V: Clone + Send + Sync + 'static {
+    async fn handle_message<D: Delegate>(&self, message: Get<K, V>, _context: &ActorContext<D>) -> Option<V> {
+        self.entries.get(&message.0).cloned()
+    }
+}
+
+fn error_of<A: Actor>() -> &'static str {
+    core::any::type_name::<A::Error>()
+}
+
+fn main() {
+    assert_eq!(error_of::<Cache<u64, String>>(), core::any::type_name::<CacheError<u64>>());
+
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        let system = Fluxion::new("system", ());
+        let cache = system.add(Cache { entries: BTreeMap::from([(1u64, "one".to_string())]) }).await.unwrap();
+
+        assert_eq!(cache.send(Get(1, core::marker::PhantomData)).await.unwrap().as_deref(), Some("one"));
+        assert_eq!(cache.send(Get(2, core::marker::PhantomData)).await.unwrap(), None);
+
+        system.add(Counter(0u32)).await.unwrap();
+    });
+}
@@ -0,0 +1,39 @@
+use fluxion::{actor, message, ActorContext, Delegate, Fluxion, Handler, Message, MessageID, MessageSender};
+
+#[actor]
+struct Store;
+
+/// A generic message whose result type refers to its generic parameter
+#[message(Option<T>)]
+struct Take<T: Send + Sync + 'static>(T);
+
+/// A generic message with a where clause
+#[message(Vec<T>)]
+struct Repeat<T>(T, usize) where T: Clone + Send + Sync + 'static;
+
+impl<T: Send + Sync + 'static> Handler<Take<T>> for Store {
+    async fn handle_message<D: Delegate>(&self, message: Take<T>, _context: &ActorContext<D>) -> Option<T> {
+        Some(message.0)
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Handler<Repeat<T>> for Store {
+    async fn handle_message<D: Delegate>(&self, message: Repeat<T>, _context: &ActorContext<D>) -> Vec<T> {
+        vec![message.0; message.1]
+    }
+}
+
+fn main() {
+    // Every instantiation shares the same id
+    assert_eq!(<Take<u8> as MessageID>::ID, <Take<String> as MessageID>::ID);
+    let _: <Take<u8> as Message>::Result = Some(1u8);
+
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        let system = Fluxion::new("system", ());
+        let store = system.add(Store).await.unwrap();
+
+        assert_eq!(store.send(Take(5u8)).await.unwrap(), Some(5));
+        assert_eq!(store.send(Take("five")).await.unwrap(), Some("five"));
+        assert_eq!(store.send(Repeat('a', 3)).await.unwrap(), ['a'; 3]);
+    });
+}
@@ -0,0 +1,8 @@
+use fluxion::MessageID;
+
+/// A message without `#[message]`, which therefore has no id
+struct Plain;
+
+fn main() {
+    let _ = <Plain as MessageID>::ID;
+}
@@ -29,26 +29,47 @@ impl Parse for MessageParams {
     }
 }
 
-#[proc_macro_attribute]
-pub fn message(attr: TokenStream, item: TokenStream) -> TokenStream {
+/// Renders a type as a string, removing the spaces that [`ToString`] inserts between tokens
+/// unless they separate two identifiers, so that `Vec<u8>` is rendered as `Vec<u8>` and not `Vec < u8 >`.
+fn type_name(ty: &Type) -> String {
+    let tokens = ty.to_token_stream().to_string();
+    let mut name = String::with_capacity(tokens.len());
+    let mut chars = tokens.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == ' ' {
+            let before = name.chars().last().is_some_and(|c: char| c.is_alphanumeric() || c == '_');
+            let after = chars.peek().is_some_and(|c| c.is_alphanumeric() || *c == '_');
+
+            // Keep spaces between identifiers, such as in `dyn Trait`, and after commas
+            let keep = (before && after) || name.ends_with(',');
+            if !keep {
+                continue;
+            }
+        }
+        name.push(c);
+    }
 
-    // Get the parameters
-    let params = if attr.is_empty() {
-        MessageParams {
+    name
+}
+
+impl Default for MessageParams {
+    fn default() -> Self {
+        Self {
             result_type: Type::Tuple(syn::TypeTuple {
                 paren_token: syn::token::Paren(Span::call_site()),
                 elems: Punctuated::new()
             }),
             name: None,
         }
-    } else {
-        syn::parse_macro_input!(attr as MessageParams)
-    };
-
+    }
+}
 
-    // Get the item's name
-    let item_name = item.clone();
-    let item_name = syn::parse_macro_input!(item_name as DeriveInput).ident;
+/// Generates the [`Message`] and [`MessageID`] implementations for the given item
+fn message_impls(input: &DeriveInput, params: MessageParams) -> TokenStream2 {
+    // Get the item's name and generics
+    let item_name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
 
     // Default the id to the path of the item
     // if no id is provided.
@@ -65,32 +86,85 @@ pub fn message(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
 
-    
-    // Convert to tokenstream 2 for quote.
-    let item: TokenStream2 = item.into();
-
     // Extract the result type
     let result_type = params.result_type;
+    let response_name = type_name(&result_type);
     
     quote! {
-        #item
-
-        impl fluxion::MessageID for #item_name {
+        impl #impl_generics fluxion::MessageID for #item_name #type_generics #where_clause {
             const ID: &'static str = #id;
+            const RESPONSE_NAME: &'static str = #response_name;
         }
 
-        impl fluxion::Message for #item_name {
+        impl #impl_generics fluxion::Message for #item_name #type_generics #where_clause {
             type Result = #result_type;
         }
+    }
+}
+
+#[proc_macro_attribute]
+pub fn message(attr: TokenStream, item: TokenStream) -> TokenStream {
+
+    // Get the parameters
+    let params = if attr.is_empty() {
+        MessageParams::default()
+    } else {
+        syn::parse_macro_input!(attr as MessageParams)
+    };
+
+    let input = item.clone();
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    let impls = message_impls(&input, params);
+
+    // Convert to tokenstream 2 for quote.
+    let item: TokenStream2 = item.into();
+
+    quote! {
+        #item
+
+        #impls
     }.into()
 }
 
+/// Parses the `#[fluxion(result = Type, id = "name")]` helper attributes of a message
+fn derive_params(input: &DeriveInput) -> syn::Result<MessageParams> {
+    let mut params = MessageParams::default();
+
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("fluxion")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("result") {
+                params.result_type = meta.value()?.parse()?;
+                Ok(())
+            } else if meta.path.is_ident("id") {
+                params.name = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `result` or `id`"))
+            }
+        })?;
+    }
+
+    Ok(params)
+}
+
+#[proc_macro_derive(Message, attributes(fluxion))]
+pub fn derive_message(item: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(item as DeriveInput);
+
+    match derive_params(&input) {
+        Ok(params) => message_impls(&input, params).into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
 
 #[proc_macro_attribute]
 pub fn actor(attr: TokenStream, item: TokenStream) -> TokenStream {
-    // Get the item's name
-    let item_name = item.clone();
-    let item_name = syn::parse_macro_input!(item_name as DeriveInput).ident;
+    // Get the item's name and generics
+    let input = item.clone();
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    let item_name = input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
 
     // Get the optional error type, defaulting to ()
     let error_type = if attr.is_empty() {
@@ -107,7 +181,7 @@ pub fn actor(attr: TokenStream, item: TokenStream) -> TokenStream {
     quote! {
         #item
 
-        impl fluxion::Actor for #item_name {
+        impl #impl_generics fluxion::Actor for #item_name #type_generics #where_clause {
             type Error = #error_type;
         }
     }.into()